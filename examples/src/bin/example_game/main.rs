@@ -24,6 +24,10 @@ use {
             Scene, 
         },
         registration::relation::Parent,
+        physics::{
+            character::CharacterController,
+            collider::{Collider, ColliderShape},
+        },
         scripting::{
             Script,
             Scriptable,
@@ -104,6 +108,24 @@ fn main() {
     );
     engine.scene.write().unwrap().add_child(player_container.clone());
 
+    // the player walks on the ground and can not pass through colliders
+    let player_id = player_container.read().unwrap().get_id();
+    engine.scene.read().unwrap().set_character_controller(player_id, CharacterController::new(0.9, 2.0));
+
+    let ground = Group::new(
+        Some("Ground"),
+        None,
+        Some(Vector3(0.0, -1.5, 0.0)),
+        None,
+        None,
+        true,
+        engine.globals.clone(),
+        None
+    );
+    let ground_id = ground.read().unwrap().get_id();
+    engine.scene.read().unwrap().set_collider(ground_id, Collider::new(ColliderShape::Aabb { half_extents: Vector3(100.0, 0.5, 100.0) })).unwrap();
+    engine.scene.write().unwrap().add_child(ground);
+
     let player_model = Obj::from_obj(
         Some("XYZ"),
         "assets/standard-assets/models/shapes/cube.obj",
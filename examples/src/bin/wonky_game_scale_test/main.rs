@@ -1,4 +1,4 @@
-use feo_oop_engine::scene::game_object::group::Group;
+use feo_oop_engine::scene::game_object::{GameObject, group::Group};
 
 mod scr;
 
@@ -21,6 +21,7 @@ use {
             Scene, 
         },
         registration::relation::Parent,
+        physics::character::CharacterController,
         scripting::Script,
         components::RGB
     },
@@ -116,6 +117,12 @@ fn main() {
     );
     engine.scene.write().unwrap().add_child(player_container.clone());
 
+    // the player flies so it is not pulled down by gravity
+    let player_id = player_container.read().unwrap().get_id();
+    let mut controller = CharacterController::new(1.0, 2.0);
+    controller.gravity_scale = 0.0;
+    engine.scene.read().unwrap().set_character_controller(player_id, controller);
+
     let player_model = Obj::from_obj(
         Some("XYZ"),
         "assets/standard-assets/models/shapes/cube.obj",
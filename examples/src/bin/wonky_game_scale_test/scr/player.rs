@@ -1,6 +1,6 @@
 use std::any::Any;
 
-use feo_oop_engine::scene::game_object::group::Group;
+use feo_oop_engine::scene::game_object::{GameObject, group::Group};
 
 
 
@@ -18,6 +18,7 @@ use {
     },
     feo_math::{
         rotation::quaternion::Quaternion,
+        linear_algebra::vector3::Vector3,
         axes::Axes,
     },
     std::{
@@ -76,7 +77,7 @@ pub async fn start<'r>(this: Arc<RwLock<Group>>, _: EngineGlobals) -> Swap {
 
 #[macro_rules_attribute(frame_script!)]
 pub async fn frame<'r>(this: Arc<RwLock<Group>>, engine_globals: EngineGlobals) -> Swap {
-    let (pewing, pos, rot) = {
+    let (pewing, pos, rot, id, motion) = {
         let this = this.clone();
         let mut this = this.write().unwrap();
         let mut globals = downcast!(*this.get_globals().unwrap(), dyn Global, PlayerGlobals);
@@ -86,17 +87,19 @@ pub async fn frame<'r>(this: Arc<RwLock<Group>>, engine_globals: EngineGlobals)
         let delta = (elapsed.as_secs() as f32 + (elapsed.subsec_nanos() as f32 / 1_000_000_000.0));
         let axes: Axes<f32> = Axes::<f32>::from(this.subspace.rotation);
 
+        // the character controller moves the player on the next physics step without passing through walls
+        let mut motion = Vector3(0.0, 0.0, 0.0);
         if globals.forward {
-            this.subspace.center = this.subspace.center - axes.z * delta * 10.0;
+            motion = motion - axes.z * delta * 10.0;
         }
         if globals.backward {
-            this.subspace.center = this.subspace.center + axes.z * delta * 10.0;
+            motion = motion + axes.z * delta * 10.0;
         }
         if globals.right {
-            this.subspace.center = this.subspace.center + axes.x * delta * 10.0;
+            motion = motion + axes.x * delta * 10.0;
         }
         if globals.left {
-            this.subspace.center = this.subspace.center - axes.x * delta * 10.0;
+            motion = motion - axes.x * delta * 10.0;
         }
         
         if globals.rot_up {
@@ -115,9 +118,11 @@ pub async fn frame<'r>(this: Arc<RwLock<Group>>, engine_globals: EngineGlobals)
         let pewing = globals.pew && globals.cooldown.elapsed().subsec_nanos() as f32 / 1_000_000_000.0 >= globals.cooldown_time && { globals.cooldown = Instant::now(); true };
 
         this.set_globals(Box::new(globals) as Box<dyn Global>).unwrap();
-        (pewing, this.subspace.center, this.subspace.rotation,)
+        (pewing, this.subspace.center, this.subspace.rotation, this.get_id(), motion)
     };
 
+    engine_globals.scene.read().unwrap().move_character(&id, motion).unwrap();
+
     if pewing {
         // unless adding to self you don't know if the lock has been lifted
         // to cleanly do this therefore rather than awaiting locks generate
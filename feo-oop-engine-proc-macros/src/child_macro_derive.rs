@@ -5,8 +5,11 @@ pub(crate) fn impl_child_macro(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
     let gen = quote! {
         impl Child for #name {
-            fn get_parent(&self) -> ParentWrapper { self.parent.clone() }
-            unsafe fn set_parent(&mut self, parent: ParentWrapper) { self.parent = parent; }
+            fn get_parent(&self) -> Option<ParentWrapper> { self.parent.upgrade() }
+            unsafe fn set_parent(&mut self, parent: ParentWrapper) {
+                self.parent = parent.downgrade();
+                Transform::invalidate_all(); // the world transform depends on the parent
+            }
         }
     };
     gen.into()
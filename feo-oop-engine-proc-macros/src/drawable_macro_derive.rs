@@ -19,6 +19,7 @@ pub(crate) fn impl_drawable_macro(ast: &syn::DeriveInput) -> TokenStream {
 
     let mut mesh: Mesh = Mesh::None;
     let mut visible_var: bool = false;
+    let mut active_var: bool = false;
 
     match data {
         Data::Struct( DataStruct { fields, .. } ) => match fields {
@@ -111,6 +112,8 @@ pub(crate) fn impl_drawable_macro(ast: &syn::DeriveInput) -> TokenStream {
                             }
                         } else if path.is_ident("bool") && field.ident.clone().unwrap() == Ident::new("visible", Span::call_site()){
                             visible_var = true;
+                        } else if path.is_ident("bool") && field.ident.clone().unwrap() == Ident::new("active", Span::call_site()){
+                            active_var = true;
                         }
                     };
                 });
@@ -285,6 +288,17 @@ pub(crate) fn impl_drawable_macro(ast: &syn::DeriveInput) -> TokenStream {
         true => quote! { self.visible },
         false => quote! { true }
     };
+
+    let set_visible = match visible_var {
+        true => quote! { self.visible = visible; Ok(()) },
+        false => quote! { Err("This object has no visible field.") }
+    };
+
+    // inactive objects are not rendered either
+    let rendered = match active_var {
+        true => quote! { self.active && #visible },
+        false => visible.clone()
+    };
     
     let render_self = match mesh {
         Mesh::None => quote! { /* none */ },
@@ -340,7 +354,8 @@ pub(crate) fn impl_drawable_macro(ast: &syn::DeriveInput) -> TokenStream {
             load_light = quote!{
                 match this{
                     ParentWrapper::GameObject(game_object) => {
-                        lighting_pass_manager.lights.push(self.cast_light_arc_rwlock(game_object).unwrap());
+                        // this is the handle of self
+                        lighting_pass_manager.lights.push(unsafe { self.cast_light_arc_rwlock(game_object) }.unwrap());
                     },
                     ParentWrapper::Scene(_) => unreachable!(),
                 }
@@ -363,7 +378,7 @@ pub(crate) fn impl_drawable_macro(ast: &syn::DeriveInput) -> TokenStream {
 
                 let pipeline = draw_pass_manager.pipeline.clone();
 
-                if #visible {
+                if #rendered {
                     #render_self
       
                     for child in self.children.clone(){
@@ -408,7 +423,7 @@ pub(crate) fn impl_drawable_macro(ast: &syn::DeriveInput) -> TokenStream {
             // }
 
             fn load_into_managers(&self, this: ParentWrapper, draw_pass_manager: &mut DrawPassManager, lighting_pass_manager: &mut LightingPassManager){
-                if #visible {
+                if #rendered {
                     #load_light
                     
                     let vertex_buffers = &mut draw_pass_manager.vertex_buffers;
@@ -444,6 +459,8 @@ pub(crate) fn impl_drawable_macro(ast: &syn::DeriveInput) -> TokenStream {
             #[inline]
             fn get_visible(&self) -> bool { #visible }
 
+            fn set_visible(&mut self, visible: bool) -> Result<(), &'static str> { #set_visible }
+
             #mesh_fns
         }
     };
@@ -1,12 +1,27 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::{Data, DataStruct, Fields, FieldsNamed, Type};
 
 pub(crate) fn impl_gameobject_macro(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
     let attributes = &ast.attrs;
+
+    let active_var = match &ast.data {
+        Data::Struct( DataStruct { fields: Fields::Named( FieldsNamed { named, .. } ), .. } ) => named.iter().any(|field| {
+            matches!(&field.ty, Type::Path(type_path) if type_path.path.is_ident("bool")) && 
+                field.ident.as_ref().is_some_and(|ident| ident == "active")
+        }),
+        _ => false,
+    };
+    let (active, set_active) = match active_var {
+        true => (quote!(self.active), quote!(self.active = active; Ok(()))),
+        false => (quote!(true), quote!(Err("This GameObject has no active field.")))
+    };
     
     let mut dyn_camera_cast = quote!(Err(()));
     let mut dyn_light_cast = quote!(Err(()));
+    let mut camera_ref = quote!(None);
+    let mut light_ref = quote!(None);
     attributes.iter().for_each(|attr| {
         if attr.path.is_ident("camera") { // TODO: make part of drawable
             dyn_camera_cast = quote!(
@@ -14,40 +29,41 @@ pub(crate) fn impl_gameobject_macro(ast: &syn::DeriveInput) -> TokenStream {
                 let this = unsafe {Arc::from_raw(this_ptr)};
                 Ok(this as Arc<RwLock<dyn Camera>>)
             );
+            camera_ref = quote!(Some(self as &dyn Camera));
         } else if attr.path.is_ident("light") { // already part of drawable
             dyn_light_cast = quote!(
                 let this_ptr = Arc::into_raw(this).cast::<RwLock<Self>>();
                 let this = unsafe {Arc::from_raw(this_ptr)};
                 Ok(this as Arc<RwLock<dyn Light>>)
             );
+            light_ref = quote!(Some(self as &dyn Light));
         }
     });
 
     let gen = quote! {
         impl GameObject for #name {
             fn as_any(&self) -> &dyn Any { self }
-            fn cast_camera_arc_rwlock(&self, this: Arc<RwLock<dyn GameObject>>) -> Result<Arc<RwLock<dyn Camera>>, ()> { #dyn_camera_cast }
-            fn cast_light_arc_rwlock(&self, this: Arc<RwLock<dyn GameObject>>) -> Result<Arc<RwLock<dyn Light>>, ()> { #dyn_light_cast  }
+            fn as_any_mut(&mut self) -> &mut dyn Any { self }
+            unsafe fn cast_camera_arc_rwlock(&self, this: Arc<RwLock<dyn GameObject>>) -> Result<Arc<RwLock<dyn Camera>>, ()> { #dyn_camera_cast }
+            unsafe fn cast_light_arc_rwlock(&self, this: Arc<RwLock<dyn GameObject>>) -> Result<Arc<RwLock<dyn Light>>, ()> { #dyn_light_cast  }
+            fn as_camera_ref(&self) -> Option<&dyn Camera> { #camera_ref }
+            fn as_light_ref(&self) -> Option<&dyn Light> { #light_ref }
         
             fn get_id(&self) -> ID { self.id.clone() } // TOFIX
+            fn type_name(&self) -> &'static str { stringify!(#name) }
+
+            fn get_active(&self) -> bool { #active }
+            fn set_active(&mut self, active: bool) -> Result<(), &'static str> { #set_active }
         
-            fn get_subspace(&self) -> Space{
-                match self.parent.clone() {
-                    ParentWrapper::GameObject(game_object) =>
-                        self.subspace.join(game_object.read().unwrap().get_subspace()),
-                    ParentWrapper::Scene(scene) =>
-                        self.subspace.join(scene.read().unwrap().worldspace)
-                }
-            }
-            
-            fn get_inversed_subspace(&self) -> Space{
-                match self.parent.clone() {
-                    ParentWrapper::GameObject(game_object) =>
-                        self.subspace.join_reverse(game_object.read().unwrap().get_inversed_subspace()),
-                    ParentWrapper::Scene(scene) =>
-                        self.subspace.join_reverse(scene.read().unwrap().worldspace.join_reverse(Space::identity()))
-                }
-            }
+            fn get_transform(&self) -> &Transform { &self.subspace }
+            fn get_transform_mut(&mut self) -> &mut Transform { &mut self.subspace }
+
+            fn set_subspace(&mut self, subspace: feo_math::utils::space::Space) { self.subspace.set_local(subspace); }
+            fn translate(&mut self, vector: feo_math::linear_algebra::vector3::Vector3<f32>) { self.subspace.translate(vector); }
+            fn rotate(&mut self, rotation: feo_math::rotation::quaternion::Quaternion<f32>) { self.subspace.rotate(rotation); }
+            fn scale(&mut self, factor: feo_math::linear_algebra::vector3::Vector3<f32>) { self.subspace.scale(factor); }
+            fn look_at(&mut self, target: feo_math::linear_algebra::vector3::Vector3<f32>, up: feo_math::linear_algebra::vector3::Vector3<f32>) { self.subspace.look_at_up(target, up); }
+            fn rotate_towards(&mut self, target: feo_math::linear_algebra::vector3::Vector3<f32>, max_angle: f32) { self.subspace.rotate_towards(target, max_angle); }
         }
     };
     gen.into()
@@ -9,6 +9,10 @@ pub(crate) fn impl_scriptable_macro(ast: &syn::DeriveInput) -> TokenStream {
     let gen = quote! {
         impl Scriptable for #name {
             fn spawn_script_core(&mut self, this: Arc<RwLock<dyn GameObject>>, spawner: Spawner){
+                if !self.get_active() { // pauses the whole subtree
+                    return;
+                }
+
                 let this_ptr = Arc::into_raw(this).cast::<RwLock<Self>>();
                 let this = unsafe {Arc::from_raw(this_ptr)};
         
@@ -39,6 +43,19 @@ pub(crate) fn impl_scriptable_macro(ast: &syn::DeriveInput) -> TokenStream {
             }
         
             fn spawn_script_handler<'a>(&mut self, this: Arc<RwLock<dyn GameObject>>, spawner: Spawner, event: Event<'static, UserEvent<Arc<dyn Any + Send + Sync>>>){ // DID removed box continue
+                // inactive objects still get to clean up before being removed
+                if !self.get_active() && !matches!(event, Event::UserEvent(UserEvent::Despawning(_))) {
+                    return;
+                }
+
+                self.spawn_own_script_handler(this, spawner.clone(), event.clone());
+                
+                self.children.clone().into_iter().for_each(|game_object| {
+                    game_object.clone().write().unwrap().spawn_script_handler(game_object, spawner.clone(), event.clone());
+                });
+            }
+        
+            fn spawn_own_script_handler(&mut self, this: Arc<RwLock<dyn GameObject>>, spawner: Spawner, event: Event<'static, UserEvent<Arc<dyn Any + Send + Sync>>>){
                 let this_ptr = Arc::into_raw(this).cast::<RwLock<Self>>();
                 let this = unsafe {Arc::from_raw(this_ptr)};
         
@@ -55,10 +72,6 @@ pub(crate) fn impl_scriptable_macro(ast: &syn::DeriveInput) -> TokenStream {
                         }
                     }
                 }
-                
-                self.children.clone().into_iter().for_each(|game_object| {
-                    game_object.clone().write().unwrap().spawn_script_handler(game_object, spawner.clone(), event.clone());
-                });
             }
         
             fn get_globals(&self) -> Result<Box<dyn Global>, &'static str> {
@@ -73,6 +86,24 @@ pub(crate) fn impl_scriptable_macro(ast: &syn::DeriveInput) -> TokenStream {
                 }
             }
         
+            fn has_script(&self) -> bool {
+                self.script.is_some()
+            }
+        
+            fn restart_script(&mut self) {
+                if let Some(script) = self.script.as_mut() {
+                    script.has_started = false;
+                }
+
+                self.children.clone().into_iter().for_each(|game_object| {
+                    game_object.write().unwrap().restart_script();
+                });
+            }
+        
+            fn get_script_name(&self) -> Option<String> {
+                self.script.as_ref().and_then(|script| script.name.clone())
+            }
+        
             fn set_globals(&mut self, globals: Box<dyn Global>) -> Result<(), &'static str>{
                 match self.script.as_mut() {
                     Some(s) => Ok(s.globals = Some(globals)),
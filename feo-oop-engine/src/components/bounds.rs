@@ -0,0 +1,196 @@
+//! Axis aligned bounding boxes and rays.
+//!
+//! Used by the spatial index of a scene to answer overlap, nearest neighbour and ray queries.
+//!
+use feo_math::{
+    linear_algebra::vector3::Vector3,
+    utils::space::Space,
+};
+
+/// An axis aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+}
+
+impl Aabb {
+    /// Creates a box from two opposite corners.
+    pub fn new(a: Vector3<f32>, b: Vector3<f32>) -> Self {
+        Aabb {
+            min: Vector3(a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)),
+            max: Vector3(a.0.max(b.0), a.1.max(b.1), a.2.max(b.2)),
+        }
+    }
+
+    /// Creates an empty box at a point.
+    pub fn point(point: Vector3<f32>) -> Self {
+        Aabb { min: point, max: point }
+    }
+
+    /// Creates a box around a sphere.
+    pub fn sphere(center: Vector3<f32>, radius: f32) -> Self {
+        let radius = radius.abs();
+        Aabb {
+            min: center - radius,
+            max: center + radius,
+        }
+    }
+
+    /// Creates the smallest box containing every point. Returns None if there are no points.
+    pub fn from_points<I: IntoIterator<Item = Vector3<f32>>>(points: I) -> Option<Self> {
+        points.into_iter().fold(None, |bounds: Option<Aabb>, point| match bounds {
+            Some(bounds) => Some(bounds.union(&Aabb::point(point))),
+            None => Some(Aabb::point(point)),
+        })
+    }
+
+    /// Gets the smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: Vector3(self.min.0.min(other.min.0), self.min.1.min(other.min.1), self.min.2.min(other.min.2)),
+            max: Vector3(self.max.0.max(other.max.0), self.max.1.max(other.max.1), self.max.2.max(other.max.2)),
+        }
+    }
+
+    /// Grows the box by a margin on every side.
+    pub fn expand(&self, margin: f32) -> Aabb {
+        Aabb {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+
+    /// Checks if the boxes touch or overlap.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.0 <= other.max.0 && self.max.0 >= other.min.0 &&
+        self.min.1 <= other.max.1 && self.max.1 >= other.min.1 &&
+        self.min.2 <= other.max.2 && self.max.2 >= other.min.2
+    }
+
+    /// Checks if the other box is entirely within this box.
+    pub fn contains(&self, other: &Aabb) -> bool {
+        self.min.0 <= other.min.0 && self.max.0 >= other.max.0 &&
+        self.min.1 <= other.min.1 && self.max.1 >= other.max.1 &&
+        self.min.2 <= other.min.2 && self.max.2 >= other.max.2
+    }
+
+    /// Checks if a point is within the box.
+    pub fn contains_point(&self, point: Vector3<f32>) -> bool {
+        self.contains(&Aabb::point(point))
+    }
+
+    /// Gets the center of the box.
+    pub fn center(&self) -> Vector3<f32> {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Gets the distances from the center to the faces of the box.
+    pub fn half_extents(&self) -> Vector3<f32> {
+        (self.max - self.min) * 0.5
+    }
+
+    /// Gets the surface area of the box.
+    pub fn surface_area(&self) -> f32 {
+        let size = self.max - self.min;
+        2.0 * (size.0 * size.1 + size.1 * size.2 + size.2 * size.0)
+    }
+
+    /// Gets the point within the box closest to a point.
+    pub fn closest_point(&self, point: Vector3<f32>) -> Vector3<f32> {
+        Vector3(
+            point.0.max(self.min.0).min(self.max.0),
+            point.1.max(self.min.1).min(self.max.1),
+            point.2.max(self.min.2).min(self.max.2),
+        )
+    }
+
+    /// Gets the squared distance from a point to the box. Points within the box are 0 away.
+    pub fn distance_squared(&self, point: Vector3<f32>) -> f32 {
+        let offset = point - self.closest_point(point);
+        Vector3::dot_product(offset, offset)
+    }
+
+    /// Gets the box containing this box after it is moved from a local space into its superspace.
+    /// # Arguments
+    /// * `space` - The local to world space. e.g. `GameObject::get_subspace`
+    pub fn transform(&self, space: &Space) -> Aabb {
+        let m = space.build().m;
+        let (min, max) = ([self.min.0, self.min.1, self.min.2], [self.max.0, self.max.1, self.max.2]);
+
+        // each axis of the result is the translation plus the extremes of every term
+        let mut new_min = [m[0][3], m[1][3], m[2][3]];
+        let mut new_max = new_min;
+        for i in 0..3 {
+            for j in 0..3 {
+                let a = m[i][j] * min[j];
+                let b = m[i][j] * max[j];
+                new_min[i] += a.min(b);
+                new_max[i] += a.max(b);
+            }
+        }
+        Aabb {
+            min: Vector3::from(new_min),
+            max: Vector3::from(new_max),
+        }
+    }
+
+    /// Gets the distance along a ray at which it enters the box. Rays starting within the box enter it at 0.
+    /// # Arguments
+    /// * `ray` - The ray.
+    /// * `max_distance` - The distance along the ray after which the box is ignored.
+    pub fn intersect_ray(&self, ray: &Ray, max_distance: f32) -> Option<f32> {
+        let origin = [ray.origin.0, ray.origin.1, ray.origin.2];
+        let direction = [ray.direction.0, ray.direction.1, ray.direction.2];
+        let (min, max) = ([self.min.0, self.min.1, self.min.2], [self.max.0, self.max.1, self.max.2]);
+
+        let mut enter = 0.0_f32;
+        let mut exit = max_distance;
+        for axis in 0..3 {
+            if direction[axis].abs() <= f32::EPSILON {
+                // parallel to the slab
+                if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let inverse = 1.0 / direction[axis];
+            let near = (min[axis] - origin[axis]) * inverse;
+            let far = (max[axis] - origin[axis]) * inverse;
+            enter = enter.max(near.min(far));
+            exit = exit.min(near.max(far));
+            if enter > exit {
+                return None;
+            }
+        }
+        Some(enter)
+    }
+}
+
+/// A half line starting at an origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vector3<f32>,
+    pub direction: Vector3<f32>, // unit length
+}
+
+impl Ray {
+    /// Creates a new ray. The direction is normalized. Returns None if the direction has no length.
+    /// # Arguments
+    /// * `origin` - The point the ray starts at.
+    /// * `direction` - The direction the ray points in.
+    pub fn new(origin: Vector3<f32>, direction: Vector3<f32>) -> Option<Self> {
+        if direction.norm() <= f32::EPSILON {
+            return None;
+        }
+        Some(Ray {
+            origin,
+            direction: direction.unit_vector(),
+        })
+    }
+
+    /// Gets the point a distance along the ray.
+    pub fn at(&self, distance: f32) -> Vector3<f32> {
+        self.origin + self.direction * distance
+    }
+}
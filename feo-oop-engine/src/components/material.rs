@@ -92,6 +92,24 @@ impl Default for Material {
 }
 
 impl Material {
+    /// Creates a white material showing a texture as its diffuse and ambient color.
+    /// e.g. the texture of a camera rendering to a texture. See `graphics::render_target`.
+    pub fn from_texture(texture: Arc<Texture>) -> Self {
+        Material {
+            name: String::from("texture"),
+            kd: Some(RGB::new(1.0, 1.0, 1.0)),
+            ka: Some(RGB::new(1.0, 1.0, 1.0)),
+            map_kd: Some(texture.clone()),
+            map_ka: Some(texture),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the texture of the diffuse color (map_Kd). It is tinted by the diffuse color (Kd).
+    pub fn set_map_kd(&mut self, map_kd: Option<Arc<Texture>>) {
+        self.map_kd = map_kd;
+    }
+
     pub fn into_set(&self, queue: Arc<Queue>) -> (fs_draw::ty::Material, [Arc<Texture>; 4]) {
         let default_texture = Texture::default(queue);
 
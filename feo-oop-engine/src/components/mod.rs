@@ -5,6 +5,8 @@
 pub mod texture;
 pub mod material;
 pub mod triangle_mesh;
+pub mod transform;
+pub mod bounds;
 use std::{iter::FromIterator, num::ParseFloatError};
 
 use feo_math::linear_algebra::vector3::Vector3;
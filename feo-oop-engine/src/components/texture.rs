@@ -25,11 +25,10 @@ use {
             ImageDimensions, 
             ImmutableImage, 
             MipmapsCount, 
-            view::ImageView
-        }, 
-        memory::pool::{
-            PotentialDedicatedAllocation, 
-            StdMemoryPoolAlloc
+            view::{
+                ImageView,
+                ImageViewAbstract
+            }
         }, 
         sampler::{
             Filter, 
@@ -50,9 +49,10 @@ lazy_static!{
 }
 
 /// A texture is an image that helps describe the surface of a model
+/// 
+/// It is either loaded from an image file or rendered to by a camera. See `graphics::render_target`.
 pub struct Texture {
-    #[allow(clippy::type_complexity)]
-    pub img_view: Arc<ImageView<Arc<ImmutableImage<Format, PotentialDedicatedAllocation<StdMemoryPoolAlloc>>>>>,
+    pub img_view: Arc<dyn ImageViewAbstract + Send + Sync>,
     pub sampler: Arc<Sampler>,
 }
 
@@ -0,0 +1,272 @@
+//! The transform of a GameObject with a cached world space.
+//!
+//! A Transform dereferences to the local Space of its GameObject so it can be used like one.
+//! The local-to-world and world-to-local spaces are cached and only rebuilt when the local
+//! space or the world space of one of the parents changed. Each time a world space is rebuilt
+//! it is given a new stamp. Children remember the stamp of the parent they were last built
+//! against which marks the whole subtree dirty once a parent changes.
+//!
+//! Checking the stamps still means walking up to the scene. To avoid that every change to a local
+//! space and every change of parent starts a new epoch. A world transform that was checked against
+//! its parents during the current epoch is returned straight away as nothing can have moved since.
+//!
+use {
+    feo_math::{
+        utils::space::Space,
+        linear_algebra::{vector3::Vector3, matrix3::Matrix3},
+        rotation::quaternion::Quaternion,
+    },
+    std::{
+        ops::{Deref, DerefMut},
+        sync::{
+            Mutex,
+            atomic::{AtomicU64, Ordering}
+        }
+    }
+};
+
+static NEXT_STAMP: AtomicU64 = AtomicU64::new(1);
+static EPOCH: AtomicU64 = AtomicU64::new(1); // 0 is never current
+
+/// The spaces needed to move between a local space and world space.
+#[derive(Debug, Clone, Copy)]
+pub struct WorldTransform {
+    pub local_to_world: Space,
+    pub world_to_local: Space,
+    pub stamp: u64, // changes every time the world transform is rebuilt
+}
+
+impl WorldTransform {
+    /// The world transform of something that is not within any space.
+    pub fn identity() -> Self {
+        WorldTransform {
+            local_to_world: Space::identity(),
+            world_to_local: Space::identity(),
+            stamp: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TransformCache {
+    parent_stamp: u64,
+    world: WorldTransform,
+    epoch: u64, // the epoch the parents were last checked in
+}
+
+/// A local space with a cached world transform.
+pub struct Transform {
+    local: Space,
+    cache: Mutex<Option<TransformCache>>,
+}
+
+impl Transform {
+    pub fn new(local: Space) -> Self {
+        Transform {
+            local,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Gets the local space.
+    pub fn get_local(&self) -> Space { self.local }
+
+    /// Sets the local space.
+    pub fn set_local(&mut self, local: Space) {
+        self.local = local;
+        *self.cache.get_mut().unwrap() = None;
+        Self::invalidate_all();
+    }
+
+    /// Starts a new epoch so that every cached world transform is checked against its parents again.
+    /// Called whenever a local space or the parent of a GameObject changes.
+    pub fn invalidate_all() {
+        EPOCH.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Gets the world transform given the world transform of the parent.
+    ///
+    /// The cached world transform is used unless the local space or the parent has changed since it was built.
+    pub fn resolve(&self, parent: &WorldTransform) -> WorldTransform {
+        self.resolve_in(parent, 0)
+    }
+
+    /// Gets the world transform, only getting the world transform of the parent if something moved since
+    /// it was last checked.
+    /// # Arguments
+    /// * `parent` - Gets the world transform of the parent.
+    pub fn resolve_with<F>(&self, parent: F) -> WorldTransform where F: FnOnce() -> WorldTransform {
+        // taken before the parents are read so a change while reading them is not missed
+        let epoch = EPOCH.load(Ordering::Relaxed);
+        if let Some(cache) = self.cache.lock().unwrap().as_ref() {
+            if cache.epoch == epoch {
+                return cache.world;
+            }
+        }
+        self.resolve_in(&parent(), epoch)
+    }
+
+    fn resolve_in(&self, parent: &WorldTransform, epoch: u64) -> WorldTransform {
+        let mut cache = self.cache.lock().unwrap();
+        match cache.as_mut() {
+            Some(cache) if cache.parent_stamp == parent.stamp => {
+                if epoch != 0 {
+                    cache.epoch = epoch;
+                }
+                cache.world
+            },
+            _ => {
+                let world = WorldTransform {
+                    local_to_world: self.local.join(parent.local_to_world),
+                    world_to_local: self.local.join_reverse(parent.world_to_local),
+                    stamp: NEXT_STAMP.fetch_add(1, Ordering::Relaxed),
+                };
+                *cache = Some(TransformCache { parent_stamp: parent.stamp, world, epoch });
+                world
+            }
+        }
+    }
+
+    /// Scales the local space along its axes.
+    pub fn scale(&mut self, factor: Vector3<f32>) {
+        self.scale_factor = self.scale_factor * Matrix3::new(
+            [factor.0, 0.0, 0.0],
+            [0.0, factor.1, 0.0],
+            [0.0, 0.0, factor.2],
+        );
+    }
+
+    /// Rotates the local space so that its -z axis points at the target and its y axis is as close to up as possible.
+    /// # Arguments
+    /// * `target` - The point to look at in the parent space.
+    /// * `up` - The up direction in the parent space.
+    pub fn look_at_up(&mut self, target: Vector3<f32>, up: Vector3<f32>) {
+        if let Some(rotation) = look_rotation(self.center, target, up) {
+            self.rotation = rotation;
+        }
+    }
+
+    /// Rotates the local space towards looking at the target by no more than max_angle radians.
+    /// # Arguments
+    /// * `target` - The point to look at in the parent space.
+    /// * `max_angle` - The largest angle in radians the space may rotate by.
+    pub fn rotate_towards(&mut self, target: Vector3<f32>, max_angle: f32) {
+        if let Some(rotation) = look_rotation(self.center, target, Vector3(0.0, 1.0, 0.0)) {
+            self.rotation = rotate_towards(self.rotation.unit_quaternion(), rotation, max_angle);
+        }
+    }
+}
+
+/// Builds the rotation of a space at `position` whose -z axis points at `target`.
+fn look_rotation(position: Vector3<f32>, target: Vector3<f32>, up: Vector3<f32>) -> Option<Quaternion<f32>> {
+    let back = position - target;
+    if back.norm() <= f32::EPSILON {
+        return None;
+    }
+    let z = back.unit_vector();
+
+    // fall back to another up if up is parallel to the direction
+    let mut x = Vector3::cross_product(up, z);
+    if x.norm() <= f32::EPSILON {
+        x = Vector3::cross_product(Vector3(1.0, 0.0, 0.0), z);
+        if x.norm() <= f32::EPSILON {
+            x = Vector3::cross_product(Vector3(0.0, 0.0, 1.0), z);
+        }
+    }
+    let x = x.unit_vector();
+    let y = Vector3::cross_product(z, x);
+
+    // the axes are the columns of the rotation matrix
+    let trace = x.0 + y.1 + z.2;
+    let rotation = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        Quaternion::new((y.2 - z.1) / s, (z.0 - x.2) / s, (x.1 - y.0) / s, 0.25 * s)
+    } else if x.0 > y.1 && x.0 > z.2 {
+        let s = (1.0 + x.0 - y.1 - z.2).sqrt() * 2.0;
+        Quaternion::new(0.25 * s, (y.0 + x.1) / s, (z.0 + x.2) / s, (y.2 - z.1) / s)
+    } else if y.1 > z.2 {
+        let s = (1.0 + y.1 - x.0 - z.2).sqrt() * 2.0;
+        Quaternion::new((y.0 + x.1) / s, 0.25 * s, (z.1 + y.2) / s, (z.0 - x.2) / s)
+    } else {
+        let s = (1.0 + z.2 - x.0 - y.1).sqrt() * 2.0;
+        Quaternion::new((z.0 + x.2) / s, (z.1 + y.2) / s, 0.25 * s, (x.1 - y.0) / s)
+    };
+    Some(rotation.unit_quaternion())
+}
+
+/// Spherically interpolates from one unit rotation to another by no more than max_angle radians.
+fn rotate_towards(from: Quaternion<f32>, to: Quaternion<f32>, max_angle: f32) -> Quaternion<f32> {
+    let mut to = to;
+    let mut dot = from.0 * to.0 + from.1 * to.1 + from.2 * to.2 + from.3 * to.3;
+    if dot < 0.0 { // take the shorter path
+        to = -to;
+        dot = -dot;
+    }
+
+    let half_angle = dot.min(1.0).acos();
+    if half_angle * 2.0 <= max_angle.max(0.0) {
+        return to;
+    }
+    let t = max_angle.max(0.0) / (half_angle * 2.0);
+
+    let sin_half_angle = half_angle.sin();
+    let a = ((1.0 - t) * half_angle).sin() / sin_half_angle;
+    let b = (t * half_angle).sin() / sin_half_angle;
+    (from * a + to * b).unit_quaternion()
+}
+
+impl From<Space> for Transform {
+    fn from(local: Space) -> Self {
+        Transform::new(local)
+    }
+}
+
+impl Deref for Transform {
+    type Target = Space;
+
+    fn deref(&self) -> &Self::Target {
+        &self.local
+    }
+}
+
+impl DerefMut for Transform {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // the caller might change the local space
+        *self.cache.get_mut().unwrap() = None;
+        Self::invalidate_all();
+        &mut self.local
+    }
+}
+
+impl Clone for Transform {
+    fn clone(&self) -> Self {
+        Transform::new(self.local)
+    }
+}
+
+impl std::fmt::Debug for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.local.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn child_follows_parent() {
+        let mut parent = Transform::new(Space::new(Some(Vector3(1.0, 0.0, 0.0)), None, None));
+        let child = Transform::new(Space::new(Some(Vector3(0.0, 2.0, 0.0)), None, None));
+        let world = |parent: &Transform| child.resolve_with(|| parent.resolve_with(WorldTransform::identity));
+
+        let before = world(&parent);
+        assert_eq!(before.local_to_world.center, Vector3(1.0, 2.0, 0.0));
+        assert_eq!(world(&parent).stamp, before.stamp);
+
+        parent.translate(Vector3(0.0, 0.0, 3.0));
+        let after = world(&parent);
+        assert_ne!(after.stamp, before.stamp);
+        assert_eq!(after.local_to_world.center, Vector3(1.0, 2.0, 3.0));
+    }
+}
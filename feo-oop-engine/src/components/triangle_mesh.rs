@@ -5,11 +5,12 @@
 use vulkano::sync;
 
 use {
-    super::{material::Material, texture::Texture},
+    super::{material::Material, texture::Texture, bounds::Aabb},
     crate::{
         shaders::fs_draw,
         components::{Normal, TextureIndex, Vertex}
     },
+    feo_math::linear_algebra::vector3::Vector3,
     std::{
         sync::Arc,
         collections::HashMap
@@ -17,7 +18,8 @@ use {
     vulkano::{
         buffer::{
             BufferUsage, 
-            CpuAccessibleBuffer
+            CpuAccessibleBuffer,
+            TypedBufferAccess
         }, 
         device::Queue,
         sync::GpuFuture
@@ -32,11 +34,18 @@ pub struct TriangleMesh {
     pub(crate) texture_indices_buffer: Option<Arc<CpuAccessibleBuffer<[TextureIndex]>>>,
     
     pub(crate) material: Option<(fs_draw::ty::Material, [Arc<Texture>; 4])>,
+
+    pub(crate) bounds: Option<Aabb>, // in the space of the gameobject
 }
 
 impl std::fmt::Debug for TriangleMesh {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "TriangleMesh debug todo")
+        f.debug_struct("TriangleMesh")
+            .field("vertices", &self.vertex_buffer.as_ref().map(|buffer| buffer.len()))
+            .field("normals", &self.normal_buffer.as_ref().map(|buffer| buffer.len()))
+            .field("texture_indices", &self.texture_indices_buffer.as_ref().map(|buffer| buffer.len()))
+            .field("has_material", &self.material.is_some())
+            .field("bounds", &self.bounds).finish()
     }
 }
 
@@ -48,6 +57,7 @@ impl TriangleMesh{
             normal_buffer: None,
             texture_indices_buffer: None,
             material: None,
+            bounds: None,
         }
     }
 
@@ -60,14 +70,22 @@ impl TriangleMesh{
             
             queue: Arc<Queue>) -> Self {
 
+        let bounds = Aabb::from_points(ordered_vertices.iter().map(|vertex| Vector3::from(vertex.position)));
+
         TriangleMesh{
             vertex_buffer: Some(CpuAccessibleBuffer::from_iter(queue.device().clone(), BufferUsage::all(), false, ordered_vertices.iter().cloned()).unwrap()),
             normal_buffer: Some(CpuAccessibleBuffer::from_iter(queue.device().clone(), BufferUsage::all(), false, ordered_normals.iter().cloned()).unwrap()),
             texture_indices_buffer: Some(CpuAccessibleBuffer::from_iter(queue.device().clone(), BufferUsage::all(), false, ordered_texture_indices.iter().cloned()).unwrap()),
             material: Some(material.into_set(queue.clone())),
+            bounds,
         }
     }
 
+    /// Gets the box containing every vertex in the space of the gameobject. None if the mesh is empty.
+    pub fn get_bounds(&self) -> Option<Aabb> {
+        self.bounds
+    }
+
     /// Create a triangle mesh from a section of an obj file.
     #[allow(clippy::type_complexity)]
     pub fn from_obj_block<'a>(block: &[&str], mtls_hashmap: &mut HashMap<String, (Arc<Material>, Box<dyn GpuFuture>)>, vertex_data: (&Vec<Box<Vertex>>, &Vec<Box<TextureIndex>>, &Vec<Box<Normal>>), queue: Arc<Queue>) -> Result<Self, &'a str> {
@@ -1,7 +1,7 @@
 //! Engine events and user defined events container.
 //! 
 use std::sync::{Arc, RwLock};
-use crate::scene::game_object::GameObject;
+use crate::{scene::game_object::GameObject, registration::id::ID, physics::Contact};
 use std::fmt::Display;
 
 /// A wrapper for winit events.
@@ -12,7 +12,24 @@ use std::fmt::Display;
 pub enum UserEvent<T: 'static + Clone + Send + Sync>{
     RebuildSwapchain,
     
-    Collision(Arc<RwLock<dyn GameObject>>, Arc<RwLock<dyn GameObject>>),
+    /// Only delivered to the two GameObjects whose colliders overlap. See `physics`.
+    Collision(Arc<RwLock<dyn GameObject>> /* this */, Arc<RwLock<dyn GameObject>> /* other */, Contact),
+    /// Only delivered to a GameObject with a trigger and the GameObject entering it. See `physics::trigger`.
+    TriggerEnter(Arc<RwLock<dyn GameObject>> /* this */, Arc<RwLock<dyn GameObject>> /* other */),
+    /// Delivered every frame after `TriggerEnter` until the GameObjects no longer overlap.
+    TriggerStay(Arc<RwLock<dyn GameObject>> /* this */, Arc<RwLock<dyn GameObject>> /* other */),
+    /// Delivered once the GameObjects no longer overlap.
+    TriggerExit(Arc<RwLock<dyn GameObject>> /* this */, Arc<RwLock<dyn GameObject>> /* other */),
+
+    // Hierarchy events. A parent ID of None refers to the scene.
+    ChildAdded(Option<ID> /* parent */, ID /* child */),
+    ChildRemoved(Option<ID> /* parent */, ID /* child */),
+    ParentChanged(ID /* child */, Option<ID> /* new parent */),
+    Spawned(ID),
+    /// Only delivered to the GameObjects that are about to be removed, before they are removed. 
+    /// The ID is of the root of the subtree being removed.
+    /// Also delivered to every GameObject of a scene that is being switched away from. See `scene::manager`.
+    Despawning(ID),
 
     WinitEvent(winit::event::Event<'static, Box<UserEvent<T>>>),
     UserEvent(T),
@@ -170,10 +170,10 @@ impl DrawPassManager {
         builder.build().unwrap()
     }
 
-    /// rebuild the swapchain and linked
+    /// Rebuilds the pipeline to draw within a viewport.
     pub fn rebuild(
             &mut self,
-            dimensions: &[u32; 2],
+            viewport: Viewport,
             render_pass: Arc<dyn RenderPassAbstract + Send + Sync>) {
         let vs = vs_draw::Shader::load(self.gfx_queue.device().clone()).unwrap();
         let fs = fs_draw::Shader::load(self.gfx_queue.device().clone()).unwrap();
@@ -184,11 +184,7 @@ impl DrawPassManager {
                 .vertex_shader(vs.main_entry_point(), ())
                 .triangle_list()
                 .viewports_dynamic_scissors_irrelevant(1)
-                .viewports(iter::once(Viewport {
-                    origin: [0.0, 0.0],
-                    dimensions: [dimensions[0] as f32, dimensions[1] as f32],
-                    depth_range: 0.0..1.0,
-                }))
+                .viewports(iter::once(viewport))
                 .fragment_shader(fs.main_entry_point(), ())
                 .depth_write(true)
                 .depth_stencil_simple_depth()
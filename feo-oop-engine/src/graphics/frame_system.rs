@@ -1,7 +1,6 @@
 use std::sync::Arc;
-use vulkano::{command_buffer::{AutoCommandBufferBuilder, SubpassContents}, device::Queue, format::Format, framebuffer::Framebuffer, framebuffer::{FramebufferAbstract, RenderPassAbstract, Subpass}, image::AttachmentImage, image::ImageUsage, image::{ImageViewAbstract, SwapchainImage, view::ImageView}, sync::GpuFuture};
+use vulkano::{pipeline::viewport::Viewport, command_buffer::{AutoCommandBufferBuilder, SubpassContents}, device::Queue, format::Format, framebuffer::Framebuffer, framebuffer::{FramebufferAbstract, RenderPassAbstract, Subpass}, image::AttachmentImage, image::ImageUsage, image::{ImageViewAbstract, view::ImageView}, sync::GpuFuture};
 use feo_math::{linear_algebra::{matrix4::Matrix4}, utils::space::Space};
-use winit::window::Window;
 use super::{draw_pass_manager::DrawPassManager, lighting_pass_manager::LightingPassManager, pass_builder::PassBuilder};
 
 /// System that contains the necessary facilities for rendering a single frame.
@@ -11,6 +10,9 @@ pub struct FrameSystem {
     render_pass: Arc<dyn RenderPassAbstract + Send + Sync>,
     framebuffers: Vec<Arc<dyn FramebufferAbstract + Send + Sync>>,
 
+    dimensions: [u32; 2],
+    viewport: Viewport, // the area of the images drawn to
+
     pub(crate) diffuse_buffer: Arc<ImageView<Arc<AttachmentImage>>>,
     pub(crate) normals_buffer: Arc<ImageView<Arc<AttachmentImage>>>,
     pub(crate) ambient_buffer: Arc<ImageView<Arc<AttachmentImage>>>,
@@ -170,6 +172,8 @@ impl FrameSystem {
             gfx_queue,
             render_pass: render_pass as Arc<_>,
             framebuffers: Vec::new(),
+            dimensions: dims,
+            viewport: full_viewport(dims),
             diffuse_buffer,
             ambient_buffer,
             specular_buffer,
@@ -221,10 +225,47 @@ impl FrameSystem {
         }
     }
     
+    /// Gets the width and height in pixels of the images rendered to.
+    pub fn get_dimensions(&self) -> [u32; 2] {
+        self.dimensions
+    }
+
+    /// Draws the scene within the largest area of the images with an aspect ratio leaving bars at the sides
+    /// or at the top and bottom. None draws across the whole images.
+    /// Returns the parts of the width and height of the images covered.
+    pub fn letterbox(&mut self, aspect_ratio: Option<f32>) -> (f32, f32) {
+        let (width, height) = (self.dimensions[0] as f32, self.dimensions[1] as f32);
+        let (x, y) = match aspect_ratio {
+            Some(aspect_ratio) if width > 0.0 && height > 0.0 => {
+                let image_aspect_ratio = width / height;
+                if image_aspect_ratio > aspect_ratio {
+                    (aspect_ratio / image_aspect_ratio, 1.0)
+                } else {
+                    (1.0, image_aspect_ratio / aspect_ratio)
+                }
+            },
+            _ => (1.0, 1.0),
+        };
+
+        let viewport = Viewport {
+            origin: [width * (1.0 - x) * 0.5, height * (1.0 - y) * 0.5],
+            dimensions: [width * x, height * y],
+            depth_range: 0.0..1.0,
+        };
+        if viewport != self.viewport {
+            self.draw_pass_manager.rebuild(viewport.clone(), self.render_pass.clone());
+            self.viewport = viewport;
+        }
+        (x, y)
+    }
+
     // Rebuilds the FrameSystem with the required dimentions
-    pub fn rebuild_dims(&mut self, images: &[Arc<ImageView<Arc<SwapchainImage<Window>>>>]){
-        let dimensions = &images[0].clone().image().dimensions().width_height();
-        self.draw_pass_manager.rebuild(dimensions, self.render_pass.clone());
+    pub fn rebuild_dims<I>(&mut self, images: &[Arc<I>])
+    where I: ImageViewAbstract + Send + Sync + 'static {
+        let dimensions = &images[0].image().dimensions().width_height();
+        self.dimensions = *dimensions;
+        self.viewport = full_viewport(*dimensions);
+        self.draw_pass_manager.rebuild(self.viewport.clone(), self.render_pass.clone());
         
         let atch_usage = ImageUsage {
             transient_attachment: true,
@@ -287,4 +328,12 @@ impl FrameSystem {
                 ) as Arc<dyn FramebufferAbstract + Send + Sync>
             ).collect::<Vec<_>>();
     }
-}
\ No newline at end of file
+}
+
+fn full_viewport(dimensions: [u32; 2]) -> Viewport {
+    Viewport {
+        origin: [0.0, 0.0],
+        dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+        depth_range: 0.0..1.0,
+    }
+}
@@ -8,6 +8,7 @@ pub mod graphics_system;
 pub mod pass_builder;
 pub mod draw_pass_manager;
 pub mod lighting_pass_manager;
+pub mod render_target;
 
 use {
     crate::{
@@ -56,6 +57,9 @@ pub trait Drawable {
     
     /// Checks if an object is visible
     fn get_visible(&self) -> bool;
+
+    /// Shows or hides the object and its children. Fails if the object has no visible field.
+    fn set_visible(&mut self, visible: bool) -> Result<(), &'static str>;
     
     /// Adds a triangle mesh to the Drawable struct
     fn add_triangle_mesh(&mut self, triangle_mesh: Arc<TriangleMesh>) -> Result<(), ()>;
@@ -0,0 +1,201 @@
+//! Cameras that render into textures.
+//!
+//! Any camera in a scene can render into a texture with `Scene::set_render_target`. Every frame the scene is
+//! rendered from each of these cameras before it is rendered from the main camera. The texture can then be
+//! shown on any material as its map_Kd, e.g. for security monitors, mirrors, portals and minimaps.
+//!
+//! A camera draws into an image of its own which is copied into the texture once it is done. So a camera
+//! that sees its own texture, like a mirror facing a mirror, sees what it rendered on the last frame.
+//!
+//! The aspect ratio of the camera follows the dimensions of the texture unless it is fixed. See `Camera::resize`.
+//!
+//! ## Example
+//! ```no_run
+//! # use feo_oop_engine::scene::game_object::{camera::Camera, obj::Obj};
+//! # use std::sync::{Arc, RwLock};
+//! # fn f(security_camera: Arc<RwLock<dyn Camera>>, monitor: Arc<RwLock<Obj>>, engine_globals: feo_oop_engine::scripting::globals::EngineGlobals) {
+//! let id = security_camera.read().unwrap().get_id();
+//! let texture = engine_globals.scene.read().unwrap()
+//!     .set_render_target(id, [512, 288], engine_globals.queue.clone())
+//!     .unwrap();
+//! monitor.write().unwrap().set_diffuse_map(texture, engine_globals.queue.clone());
+//! # }
+//! ```
+//!
+use {
+    super::frame_system::FrameSystem,
+    crate::{
+        scene::{
+            Scene,
+            game_object::GameObjectHandle,
+        },
+        registration::id::ID,
+        components::texture::Texture,
+    },
+    std::{
+        collections::HashMap,
+        fmt,
+        sync::{Arc, Mutex, RwLock},
+    },
+    vulkano::{
+        command_buffer::{AutoCommandBufferBuilder, CommandBuffer},
+        device::Queue,
+        format::{ClearValue, Format},
+        image::{
+            AttachmentImage,
+            ImageUsage,
+            view::ImageView,
+        },
+        sampler::{
+            Filter,
+            MipmapMode,
+            Sampler,
+            SamplerAddressMode,
+        },
+        sync::GpuFuture,
+    },
+};
+
+/// \[backend\] A texture a camera renders into along with everything needed to render into it.
+pub(crate) struct RenderTarget {
+    dimensions: [u32; 2],
+    frame_system: FrameSystem,
+    image: Arc<AttachmentImage>, // rendered into
+    texture_image: Arc<AttachmentImage>, // sampled by materials
+    texture: Arc<Texture>,
+}
+
+impl RenderTarget {
+    fn new(dimensions: [u32; 2], queue: Arc<Queue>) -> Result<Self, &'static str> {
+        const FORMAT: Format = Format::R8G8B8A8Srgb;
+
+        let device = queue.device().clone();
+        let image = AttachmentImage::with_usage(
+            device.clone(),
+            dimensions,
+            FORMAT,
+            ImageUsage { transfer_source: true, ..ImageUsage::none() }
+        ).map_err(|_| "The render target could not be created with these dimensions.")?;
+        let texture_image = AttachmentImage::with_usage(
+            device.clone(),
+            dimensions,
+            FORMAT,
+            ImageUsage { transfer_destination: true, sampled: true, ..ImageUsage::none() }
+        ).map_err(|_| "The render target could not be created with these dimensions.")?;
+
+        // the texture is shown black until the camera first renders into it
+        let mut builder = AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family()).unwrap();
+        builder.clear_color_image(texture_image.clone(), ClearValue::Float([0.0, 0.0, 0.0, 1.0])).unwrap();
+        builder.build().unwrap()
+            .execute(queue.clone()).unwrap()
+            .then_signal_fence_and_flush().unwrap()
+            .wait(None).unwrap();
+
+        let mut frame_system = FrameSystem::new(queue.clone(), FORMAT, dimensions);
+        frame_system.rebuild_dims(&[ImageView::new(image.clone()).unwrap()]);
+
+        let texture = Arc::new(Texture {
+            img_view: ImageView::new(texture_image.clone()).unwrap(),
+            sampler: Sampler::new(
+                device,
+                Filter::Linear,
+                Filter::Linear,
+                MipmapMode::Nearest,
+                SamplerAddressMode::ClampToEdge,
+                SamplerAddressMode::ClampToEdge,
+                SamplerAddressMode::ClampToEdge,
+                0.0,
+                1.0,
+                0.0,
+                0.0
+            ).unwrap()
+        });
+
+        Ok(RenderTarget {
+            dimensions,
+            frame_system,
+            image,
+            texture_image,
+            texture,
+        })
+    }
+}
+
+/// \[backend\] The textures the cameras within a scene render into.
+#[derive(Default, Clone)]
+pub(crate) struct RenderTargets {
+    targets: Arc<Mutex<HashMap<ID, RenderTarget>>>, // by the ID of the camera
+}
+
+impl fmt::Debug for RenderTargets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.targets.lock().unwrap().iter().map(|(id, target)| (id.clone(), target.dimensions)))
+            .finish()
+    }
+}
+
+impl Scene {
+    /// Renders a camera into a texture on every frame before the main camera. Replaces any texture the camera
+    /// already renders into. Fails if there is no camera with this ID in the scene or a dimension is 0.
+    /// # Arguments
+    /// * `camera` - The ID of the camera. It can be any camera including the main camera.
+    /// * `dimensions` - The width and height of the texture in pixels.
+    /// * `queue` - The graphics queue.
+    pub fn set_render_target(&self, camera: ID, dimensions: [u32; 2], queue: Arc<Queue>) -> Result<Arc<Texture>, &'static str> {
+        if dimensions[0] == 0 || dimensions[1] == 0 {
+            return Err("The dimensions of a render target must be positive.");
+        }
+        self.find_by_id(&camera)
+            .and_then(|game_object| game_object.as_camera())
+            .ok_or("No camera with this ID is in the scene.")?;
+
+        let target = RenderTarget::new(dimensions, queue)?;
+        let texture = target.texture.clone();
+        self.render_targets.targets.lock().unwrap().insert(camera, target);
+        Ok(texture)
+    }
+
+    /// Stops a camera rendering into its texture. The texture keeps the last image rendered into it.
+    /// # Arguments
+    /// * `camera` - The ID of the camera.
+    pub fn remove_render_target(&self, camera: &ID) -> Option<Arc<Texture>> {
+        self.render_targets.targets.lock().unwrap().remove(camera).map(|target| target.texture)
+    }
+
+    /// Gets the texture a camera renders into.
+    /// # Arguments
+    /// * `camera` - The ID of the camera.
+    pub fn get_render_texture(&self, camera: &ID) -> Option<Arc<Texture>> {
+        self.render_targets.targets.lock().unwrap().get(camera).map(|target| target.texture.clone())
+    }
+
+    /// \[backend\] Renders every active camera with a render target into its texture after the future.
+    #[allow(clippy::mutable_key_type)] // IDs hash by their index, generation and the address of their system
+    pub(crate) fn render_to_textures(&self, this: Arc<RwLock<Scene>>, mut future: Box<dyn GpuFuture>) -> Box<dyn GpuFuture> {
+        let mut targets = self.render_targets.targets.lock().unwrap();
+        targets.retain(|id, _| id.is_alive());
+
+        for (id, target) in targets.iter_mut() {
+            let camera = match self.find_by_id(id).and_then(|game_object| game_object.as_camera()) {
+                Some(camera) => camera,
+                None => continue,
+            };
+            if !camera.read().unwrap().is_active_in_hierarchy() {
+                continue;
+            }
+
+            future = self.render_camera(this.clone(), camera, &mut target.frame_system, 0, future);
+
+            let queue = target.frame_system.gfx_queue.clone();
+            let mut builder = AutoCommandBufferBuilder::primary_one_time_submit(queue.device().clone(), queue.family()).unwrap();
+            builder.copy_image(
+                target.image.clone(), [0, 0, 0], 0, 0,
+                target.texture_image.clone(), [0, 0, 0], 0, 0,
+                [target.dimensions[0], target.dimensions[1], 1], 1
+            ).unwrap();
+            future = Box::new(future.then_execute(queue, builder.build().unwrap()).unwrap());
+        }
+        future
+    }
+}
@@ -86,6 +86,7 @@
 #[macro_use] extern crate feo_oop_engine_proc_macros;
 
 pub mod scene;
+pub mod physics;
 pub mod components;
 pub mod scripting;
 pub mod event;
@@ -102,9 +103,10 @@ use {
         graphics::frame_system::FrameSystem,
         event::UserEvent,
         scripting::globals::EngineGlobals,
-        scene::Scene,
+        scene::{Scene, manager::SceneManager},
         components::texture::Texture,
-        registration::id::IDSystem
+        registration::id::IDSystem,
+        scripting::registry::ScriptRegistry,
     },
     std::{
         sync::{
@@ -240,7 +242,9 @@ impl FeoEngine {
                 surface: surface.clone(),
                 scene: scene.clone(),
                 event_loop_proxy: Arc::new(futures::lock::Mutex::new(event_loop.create_proxy())),
-                id_system: id_system.clone()
+                id_system: id_system.clone(),
+                script_registry: Arc::new(RwLock::new(ScriptRegistry::default())),
+                scene_manager: Arc::new(RwLock::new(SceneManager::new(SceneManager::DEFAULT_SCENE, scene.clone()))),
             },
 
             //instance,
@@ -256,6 +260,11 @@ impl FeoEngine {
 
     /// Allows the engine to commence excecution.
     pub fn run(&mut self) {
+        // run the active scene of the scene manager
+        let scene = self.globals.scene_manager.read().unwrap().get_active();
+        self.globals.scene = scene.clone();
+        self.scene = scene;
+
         // get swapchain and images
         let dimensions: [u32; 2] = self.surface.window().inner_size().into();
         let (mut swapchain, _) = {
@@ -324,9 +333,10 @@ impl FeoEngine {
             let event: Event<'static, UserEvent<Arc<dyn Any + Send + Sync>>> = event.to_static().unwrap();
             
             // Executor for Object event handlers
+            let h_scene = local_self.scene.clone();
             let h_executor = {
                 let (executor, spawner) = scripting::new_executor_and_spawner(local_self.globals.clone());
-                local_self.scene.read().unwrap().spawn_script_handlers(spawner, event.clone());
+                h_scene.read().unwrap().spawn_script_handlers(spawner, event.clone());
                 executor
             };
             
@@ -367,6 +377,9 @@ impl FeoEngine {
 
                     // Generate Executor and Spawner for scripts
                     let (executor, spawner) = scripting::new_executor_and_spawner(local_self.globals.clone());
+                    local_self.scene.read().unwrap().update_spatial_index();
+                    local_self.scene.read().unwrap().step_physics(spawner.clone());
+                    local_self.scene.read().unwrap().tick_lifetimes(spawner.clone());
                     local_self.scene.read().unwrap().spawn_script_cores(spawner);
 
                     // Get the next image
@@ -385,6 +398,11 @@ impl FeoEngine {
                     
                     // Run scripts to completion
                     executor.run(local_self.scene.clone()); // TODO: merge future with other future and start scripts mvd
+
+                    // Switch and load scenes
+                    let scene_manager = local_self.globals.scene_manager.clone();
+                    SceneManager::apply_requests(&scene_manager, &mut local_self.globals);
+                    local_self.scene = local_self.globals.scene.clone();
                     
                     let future = local_self.scene.read().unwrap()
                         .render(local_self.scene.clone(), &mut frame_system, image_num, acquire_future, &mut previous_frame_end)
@@ -410,7 +428,7 @@ impl FeoEngine {
             }
             
             // Force event handlers to Completion
-            h_executor.run(local_self.scene.clone());
+            h_executor.run(h_scene);
         });
     }
 }
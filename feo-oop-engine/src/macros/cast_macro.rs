@@ -9,29 +9,31 @@ macro_rules! downcast {
         }
     };
 
-    // unsafe
-    ($a:expr, Arc<$(RwLock<)?dyn GameObject$(>)+, Arc<$t:ty>) => { 
+    // checked, panics if the GameObject is of another type
+    ($a:expr, Arc<$(RwLock<)?dyn GameObject$(>)+, Arc<RwLock<$t:ty>>) => { 
         {
-            let this = Arc::into_raw($a).cast::<$t>();
-            unsafe {Arc::from_raw(this)}
+            $crate::scene::game_object::GameObjectHandle::downcast::<$t>(&$a).expect("The GameObject is of another type.")
         }
     };
-    ($a:expr, Arc<$(RwLock<)?dyn Camera$(>)?>, Arc<$t:ty>) => {
+    ($a:expr, Arc<$(RwLock<)?dyn Camera$(>)?>, Arc<RwLock<$t:ty>>) => {
         {
-            let this = Arc::into_raw($a).cast::<$t>();
+            let this = $a;
+            assert!($crate::scene::game_object::camera::Camera::as_any(&*this.read().unwrap()).is::<$t>(), "The Camera is of another type.");
+            let this = Arc::into_raw(this).cast::<RwLock<$t>>();
             unsafe {Arc::from_raw(this)}
         }
     };
-    ($a:expr, Arc<$(RwLock<)?dyn Light$(>)?>, Arc<$t:ty>) => {
+    ($a:expr, Arc<$(RwLock<)?dyn Light$(>)?>, Arc<RwLock<$t:ty>>) => {
         {
-            let this = Arc::into_raw($a).cast::<$t>();
+            let this = $a;
+            assert!($crate::scene::game_object::light::Light::as_any(&*this.read().unwrap()).is::<$t>(), "The Light is of another type.");
+            let this = Arc::into_raw(this).cast::<RwLock<$t>>();
             unsafe {Arc::from_raw(this)}
         }
     };
     ($a:expr, Arc<$(RwLock<)?dyn Any$(>)?>, Arc<$t:ty>) => {
         {
-            let this = Arc::into_raw($a).cast::<$t>();
-            unsafe {Arc::from_raw(this)}
+            $a.downcast::<$t>().ok().expect("The value is of another type.")
         }
     };
 }
@@ -78,6 +78,32 @@ macro_rules! frame_script {
     )
 }
 
+#[macro_export]
+macro_rules! on_expire {
+    (
+        $( #[$attr:meta] )*
+        $pub:vis
+        async
+        fn $fname:ident<$lt:lifetime> ($this:tt : $this_ty:ty , $engine_globals:tt : $engine_globals_ty:ty) -> Swap
+        {
+            $($body:tt)*
+        }
+    ) => (
+        $( #[$attr] )*
+        #[allow(unused_parens)]
+        $pub
+        fn $fname<$lt> ($this : $this_ty, $engine_globals : $engine_globals_ty) -> ::std::pin::Pin<::std::boxed::Box<
+            dyn ::std::future::Future<Output = Swap>
+                + ::std::marker::Send + $lt
+        >>
+        {
+            ::std::boxed::Box::pin(async move { 
+                $($body)*
+            })
+        }
+    )
+}
+
 #[macro_export]
 macro_rules! event_handler {
     (
@@ -0,0 +1,370 @@
+//! Kinematic character controllers that walk and jump through a scene without passing through colliders.
+//!
+//! A controller is attached to a GameObject with `Scene::set_character_controller`. It is an upright
+//! capsule that does not rotate with its GameObject. The scripts ask it to move with `Scene::move_character`
+//! and on the next physics step it moves as far as it can, sliding along the colliders in its way instead of
+//! stopping. It climbs ledges no higher than its step height and slopes no steeper than its slope limit
+//! and falls under gravity whenever it is not grounded.
+//!
+//! The rest of the scene sees the capsule as a collider without a rigid body. Rigid bodies are pushed out
+//! of it, rays hit it and triggers detect it.
+//!
+//! ## Example
+//! ```no_run
+//! # use feo_oop_engine::physics::character::CharacterController;
+//! # use feo_math::linear_algebra::vector3::Vector3;
+//! # fn f(player: std::sync::Arc<std::sync::RwLock<dyn feo_oop_engine::scene::game_object::GameObject>>, engine_globals: feo_oop_engine::scripting::globals::EngineGlobals) {
+//! let id = player.read().unwrap().get_id();
+//! let scene = engine_globals.scene.read().unwrap();
+//! scene.set_character_controller(id.clone(), CharacterController::new(0.5, 2.0).with_offset(Vector3(0.0, 1.0, 0.0)));
+//! # }
+//! ```
+//! Then within the frame script of the player
+//! ```ignore
+//! scene.move_character(&id, forward * speed * delta).unwrap();
+//! if jump_pressed {
+//!     scene.jump_character(&id).unwrap();
+//! }
+//! ```
+//!
+use {
+    super::{
+        PhysicsWorld,
+        Body,
+        gjk,
+        collider::WorldShape,
+    },
+    crate::{
+        scene::Scene,
+        registration::id::ID,
+        components::bounds::Aabb,
+    },
+    feo_math::linear_algebra::vector3::Vector3,
+    std::sync::Arc,
+};
+
+const MAX_PUSHES: usize = 4; // the most times a capsule is pushed out of the colliders after each part of a move
+const PART_LENGTH: f32 = 0.05; // moves are split into parts so the capsule neither passes through thin colliders nor sinks far into them
+const MAX_PARTS: usize = 32;
+
+/// A capsule moved by the scripts that slides along colliders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharacterController {
+    pub radius: f32,
+    pub height: f32, // from the bottom to the top of the capsule
+    pub offset: Vector3<f32>, // the center of the capsule relative to the position of the GameObject in world space
+    pub step_height: f32, // the highest ledge it climbs
+    pub max_slope: f32, // the steepest slope in radians it can stand on. Steeper slopes block it like walls.
+    pub jump_speed: f32,
+    pub gravity_scale: f32, // the fraction of the gravity of the scene it falls with
+
+    pub(crate) motion: Vector3<f32>, // not yet moved
+    pub(crate) vertical_speed: f32,
+    pub(crate) grounded: bool,
+}
+
+impl CharacterController {
+    /// Creates a controller centered on its GameObject.
+    /// # Arguments
+    /// * `radius` - The radius of the capsule.
+    /// * `height` - The height of the capsule from its bottom to its top.
+    pub fn new(radius: f32, height: f32) -> Self {
+        CharacterController {
+            radius,
+            height,
+            offset: Vector3(0.0, 0.0, 0.0),
+            step_height: 0.3,
+            max_slope: std::f32::consts::FRAC_PI_4,
+            jump_speed: 5.0,
+            gravity_scale: 1.0,
+            motion: Vector3(0.0, 0.0, 0.0),
+            vertical_speed: 0.0,
+            grounded: false,
+        }
+    }
+
+    /// Moves the center of the capsule away from the position of the GameObject. e.g. half the height upwards to put the origin at its feet.
+    pub fn with_offset(mut self, offset: Vector3<f32>) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Moves the character on the next physics step. Moves within the same frame add up.
+    /// # Arguments
+    /// * `motion` - The distance to move in world space.
+    pub fn move_by(&mut self, motion: Vector3<f32>) {
+        self.motion = self.motion + motion;
+    }
+
+    /// Makes the character jump at its jump speed if it is grounded. Returns whether it jumped.
+    pub fn jump(&mut self) -> bool {
+        if !self.grounded {
+            return false;
+        }
+        self.vertical_speed = self.jump_speed;
+        self.grounded = false;
+        true
+    }
+
+    /// Checks if the character was standing on a collider after the last physics step.
+    pub fn is_grounded(&self) -> bool {
+        self.grounded
+    }
+
+    /// Gets the speed the character is rising at. Negative while falling.
+    pub fn get_vertical_speed(&self) -> f32 {
+        self.vertical_speed
+    }
+
+    /// \[backend\] Gets the capsule in world space.
+    /// # Arguments
+    /// * `position` - The position of the GameObject in world space.
+    /// * `up` - The unit direction opposite gravity.
+    pub(crate) fn to_world(&self, position: Vector3<f32>, up: Vector3<f32>) -> WorldShape {
+        let center = position + self.offset;
+        let half_height = (self.height * 0.5 - self.radius).max(0.0);
+        WorldShape {
+            points: vec![center + up * half_height, center - up * half_height],
+            radius: self.radius.abs(),
+        }
+    }
+
+    /// Moves the character by one step. Returns its new position.
+    fn advance(&mut self, position: Vector3<f32>, delta: f32, gravity: Vector3<f32>, up: Vector3<f32>, obstacles: &[(&Body, Aabb)]) -> Vector3<f32> {
+        let was_grounded = self.grounded;
+        if self.grounded && self.vertical_speed <= 0.0 {
+            self.vertical_speed = 0.0;
+        } else {
+            self.vertical_speed += Vector3::dot_product(gravity, up) * self.gravity_scale * delta;
+        }
+        let motion = std::mem::replace(&mut self.motion, Vector3(0.0, 0.0, 0.0));
+        let rise = Vector3::dot_product(motion, up);
+        let across = motion - up * rise;
+        self.grounded = false;
+
+        // along the ground
+        let start = position;
+        let mut position = position;
+        if across.norm() > f32::EPSILON {
+            let slid = self.slide(start, across, up, obstacles);
+            position = slid.position;
+            if slid.blocked && was_grounded && self.step_height > 0.0 {
+                // climb onto the ledge if that gets further
+                let raised = self.slide(start, up * self.step_height, up, obstacles);
+                let moved = self.slide(raised.position, across, up, obstacles);
+                let lowered = self.slide(moved.position, -up * self.step_height, up, obstacles);
+                let distance = |end: Vector3<f32>| {
+                    let offset = end - start;
+                    (offset - up * Vector3::dot_product(offset, up)).norm()
+                };
+                if lowered.ground && distance(lowered.position) > distance(slid.position) {
+                    position = lowered.position;
+                }
+            }
+        }
+
+        // up and down
+        let slid = self.slide(position, up * (rise + self.vertical_speed * delta), up, obstacles);
+        position = slid.position;
+        if slid.ground && self.vertical_speed <= 0.0 {
+            self.grounded = true;
+            self.vertical_speed = 0.0;
+        }
+        if slid.ceiling && self.vertical_speed > 0.0 {
+            self.vertical_speed = 0.0;
+        }
+
+        // follow the ground down slopes and stairs
+        if was_grounded && !self.grounded && self.vertical_speed <= 0.0 {
+            let lowered = self.slide(position, -up * self.step_height, up, obstacles);
+            if lowered.ground {
+                position = lowered.position;
+                self.grounded = true;
+                self.vertical_speed = 0.0;
+            }
+        }
+        position
+    }
+
+    /// Moves the capsule in parts and pushes it out of the colliders after each of them.
+    fn slide(&self, start: Vector3<f32>, motion: Vector3<f32>, up: Vector3<f32>, obstacles: &[(&Body, Aabb)]) -> Slide {
+        let min_up = self.max_slope.cos();
+        let length = PART_LENGTH.min(self.radius.abs() * 0.5).max(f32::EPSILON);
+        let parts = ((motion.norm() / length).ceil() as usize).clamp(1, MAX_PARTS);
+        let part = motion * (1.0 / parts as f32);
+
+        let mut slide = Slide { position: start, ground: false, ceiling: false, blocked: false };
+        for _ in 0..parts {
+            slide.position = slide.position + part;
+            for _ in 0..MAX_PUSHES {
+                let shape = self.to_world(slide.position, up);
+                let bounds = shape.bounds();
+                let deepest = obstacles.iter()
+                    .filter(|(_, other)| bounds.intersects(other))
+                    .filter_map(|(body, _)| gjk::collide(&shape, &body.shape))
+                    .max_by(|a, b| a.depth.total_cmp(&b.depth));
+                let contact = match deepest {
+                    Some(contact) => contact,
+                    None => break,
+                };
+
+                let surface = -contact.normal; // away from the collider
+                let facing_up = Vector3::dot_product(surface, up);
+                if facing_up >= min_up {
+                    // push straight up so it does not slide down the slopes it stands on
+                    slide.position = slide.position + up * (contact.depth / facing_up);
+                    slide.ground = true;
+                } else if facing_up <= -min_up {
+                    slide.position = slide.position + surface * contact.depth;
+                    slide.ceiling = true;
+                } else {
+                    // push straight back so it does not climb steep slopes
+                    let back = surface - up * facing_up;
+                    let length = back.norm();
+                    slide.position = slide.position + back * (contact.depth / (length * length));
+                    slide.blocked |= Vector3::dot_product(back, motion) < 0.0;
+                }
+            }
+        }
+        slide
+    }
+}
+
+/// Where a capsule ended up after a move and what it touched on the way.
+struct Slide {
+    position: Vector3<f32>,
+    ground: bool,
+    ceiling: bool,
+    blocked: bool, // by a wall or a slope too steep to climb
+}
+
+impl PhysicsWorld {
+    /// Moves every active character controller by one step.
+    /// # Arguments
+    /// * `scene` - The scene.
+    /// * `delta` - The length of the step in seconds.
+    /// * `gravity` - The acceleration due to gravity.
+    pub(super) fn move_characters(&self, scene: &Scene, delta: f32, gravity: Vector3<f32>) {
+        let characters: Vec<(ID, CharacterController)> = {
+            let mut characters = self.characters.lock().unwrap();
+            characters.retain(|id, _| id.is_alive());
+            characters.iter().map(|(id, character)| (id.clone(), character.clone())).collect()
+        };
+        if characters.is_empty() {
+            return;
+        }
+        let up = up(gravity);
+        let bodies = self.bodies(scene);
+        let bodies: Vec<(&Body, Aabb)> = bodies.iter().map(|body| (body, body.shape.bounds())).collect();
+
+        characters.into_iter().for_each(|(id, mut character)| {
+            let game_object = match scene.find_by_id(&id) {
+                Some(game_object) => game_object,
+                None => return,
+            };
+            let position = {
+                let read = game_object.read().unwrap();
+                if !read.is_active_in_hierarchy() {
+                    return;
+                }
+                read.world_position()
+            };
+            let obstacles: Vec<(&Body, Aabb)> = bodies.iter()
+                .filter(|(body, _)| !Arc::ptr_eq(&body.game_object, &game_object))
+                .cloned()
+                .collect();
+            let moved = character.motion;
+            let new_position = character.advance(position, delta, gravity, up, &obstacles);
+
+            if let Some(stored) = self.characters.lock().unwrap().get_mut(&id) {
+                stored.motion = stored.motion - moved; // keeps what the scripts added in the meantime
+                stored.vertical_speed = character.vertical_speed;
+                stored.grounded = character.grounded;
+            }
+            if new_position != position {
+                game_object.write().unwrap().set_world_position(new_position);
+            }
+        });
+    }
+
+    /// \[backend\] Gets the capsules of the active character controllers in world space.
+    pub(super) fn character_bodies(&self, scene: &Scene) -> Vec<Body> {
+        let characters: Vec<(ID, CharacterController)> = {
+            let mut characters = self.characters.lock().unwrap();
+            characters.retain(|id, _| id.is_alive());
+            characters.iter().map(|(id, character)| (id.clone(), character.clone())).collect()
+        };
+        let up = up(self.settings.lock().unwrap().gravity);
+
+        characters.into_iter().filter_map(|(id, character)| {
+            let game_object = scene.find_by_id(&id)?;
+            let shape = {
+                let read = game_object.read().unwrap();
+                if !read.is_active_in_hierarchy() {
+                    return None;
+                }
+                character.to_world(read.world_position(), up)
+            };
+            Some(Body { game_object, shape })
+        }).collect()
+    }
+}
+
+/// Gets the unit direction opposite gravity. Up the y axis without gravity.
+fn up(gravity: Vector3<f32>) -> Vector3<f32> {
+    if gravity.norm() > f32::EPSILON { -gravity.unit_vector() } else { Vector3(0.0, 1.0, 0.0) }
+}
+
+impl Scene {
+    /// Attaches a character controller to a GameObject. Replaces any controller it already has.
+    ///
+    /// The GameObject should not also have a rigid body as both would move it.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `character` - The character controller.
+    pub fn set_character_controller(&self, id: ID, character: CharacterController) {
+        self.physics.characters.lock().unwrap().insert(id, character);
+    }
+
+    /// Removes the character controller of a GameObject. It stays where it is.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn remove_character_controller(&self, id: &ID) -> Option<CharacterController> {
+        self.physics.characters.lock().unwrap().remove(id)
+    }
+
+    /// Gets a copy of the character controller of a GameObject.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn get_character_controller(&self, id: &ID) -> Option<CharacterController> {
+        self.physics.characters.lock().unwrap().get(id).cloned()
+    }
+
+    /// Changes the character controller of a GameObject in place. Fails if it has no character controller.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `f` - The function changing the character controller.
+    pub fn modify_character_controller<F: FnOnce(&mut CharacterController)>(&self, id: &ID, f: F) -> Result<(), &'static str> {
+        let mut characters = self.physics.characters.lock().unwrap();
+        let character = characters.get_mut(id).ok_or("This GameObject has no character controller.")?;
+        f(character);
+        Ok(())
+    }
+
+    /// Moves a character on the next physics step. Fails if it has no character controller. See `CharacterController::move_by`.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `motion` - The distance to move in world space.
+    pub fn move_character(&self, id: &ID, motion: Vector3<f32>) -> Result<(), &'static str> {
+        self.modify_character_controller(id, |character| character.move_by(motion))
+    }
+
+    /// Makes a character jump if it is grounded. Returns whether it jumped. Fails if it has no character controller.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn jump_character(&self, id: &ID) -> Result<bool, &'static str> {
+        let mut jumped = false;
+        self.modify_character_controller(id, |character| jumped = character.jump())?;
+        Ok(jumped)
+    }
+}
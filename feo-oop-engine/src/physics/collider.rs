@@ -0,0 +1,178 @@
+//! Shapes that can be attached to GameObjects to make them collide.
+//!
+//! Every shape is convex. They are defined in the space of their GameObject and follow it as it moves.
+//!
+use {
+    crate::components::{
+        bounds::Aabb,
+        triangle_mesh::TriangleMesh,
+    },
+    feo_math::{
+        linear_algebra::vector3::Vector3,
+        utils::space::Space,
+    },
+    std::collections::HashSet,
+};
+
+/// The shape of a collider.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColliderShape {
+    /// A sphere. Scaled by the largest scale factor of its GameObject.
+    Sphere { radius: f32 },
+    /// A box that stays aligned with the world axes however its GameObject is rotated.
+    Aabb { half_extents: Vector3<f32> },
+    /// A box that rotates with its GameObject.
+    Obb { half_extents: Vector3<f32> },
+    /// A cylinder with rounded ends along the y axis. The radius is scaled by the largest scale factor of its GameObject.
+    Capsule { radius: f32, half_height: f32 },
+    /// The smallest convex shape containing the points.
+    ConvexHull { points: Vec<Vector3<f32>> },
+}
+
+impl ColliderShape {
+    /// Creates a convex hull around the vertices of a triangle mesh.
+    /// # Arguments
+    /// * `mesh` - The mesh. Its vertex buffer must not be in use by the GPU.
+    pub fn convex_hull(mesh: &TriangleMesh) -> Result<Self, &'static str> {
+        let buffer = mesh.vertex_buffer.as_ref().ok_or("The triangle mesh has no vertices.")?;
+        let vertices = buffer.read().map_err(|_| "The vertex buffer is in use.")?;
+
+        let mut seen: HashSet<[u32; 3]> = HashSet::new();
+        let mut points: Vec<Vector3<f32>> = Vec::new();
+        vertices.iter().for_each(|vertex| {
+            let [x, y, z] = vertex.position;
+            if seen.insert([x.to_bits(), y.to_bits(), z.to_bits()]) { // faces share vertices
+                points.push(Vector3::from(vertex.position));
+            }
+        });
+
+        if points.is_empty() {
+            return Err("The triangle mesh has no vertices.");
+        }
+        Ok(ColliderShape::ConvexHull { points })
+    }
+
+    /// Checks that the shape has a size. Radii and half extents must be positive and a convex hull must have points.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let positive = |value: f32| value > 0.0; // false for NaN
+        let valid = match self {
+            ColliderShape::Sphere { radius } => positive(*radius),
+            ColliderShape::Aabb { half_extents } | ColliderShape::Obb { half_extents } =>
+                positive(half_extents.0) && positive(half_extents.1) && positive(half_extents.2),
+            ColliderShape::Capsule { radius, half_height } => positive(*radius) && *half_height >= 0.0,
+            ColliderShape::ConvexHull { points } => !points.is_empty(),
+        };
+        if valid { Ok(()) } else { Err("The collider shape has no size.") }
+    }
+
+    /// Creates a box collider matching bounds given in the space of the GameObject.
+    pub fn from_bounds(bounds: &Aabb) -> Self {
+        ColliderShape::Obb { half_extents: bounds.half_extents() }
+    }
+}
+
+/// A collider attached to a GameObject. See `Scene::set_collider`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collider {
+    pub shape: ColliderShape,
+    pub offset: Vector3<f32>, // the center of the shape in the space of the GameObject
+}
+
+impl Collider {
+    /// Creates a collider centered on its GameObject.
+    pub fn new(shape: ColliderShape) -> Self {
+        Collider {
+            shape,
+            offset: Vector3(0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Moves the center of the shape within the space of the GameObject.
+    pub fn with_offset(mut self, offset: Vector3<f32>) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// \[backend\] Moves the shape into world space.
+    pub(crate) fn to_world(&self, space: &Space) -> WorldShape {
+        let m = space.build().m;
+        let apply = |point: Vector3<f32>| Vector3(
+            m[0][0] * point.0 + m[0][1] * point.1 + m[0][2] * point.2 + m[0][3],
+            m[1][0] * point.0 + m[1][1] * point.1 + m[1][2] * point.2 + m[1][3],
+            m[2][0] * point.0 + m[2][1] * point.1 + m[2][2] * point.2 + m[2][3],
+        );
+        let axis_scale = |i: usize| Vector3(m[0][i], m[1][i], m[2][i]).norm();
+        let scale = Vector3(axis_scale(0), axis_scale(1), axis_scale(2));
+        let max_scale = scale.0.max(scale.1).max(scale.2);
+
+        let center = apply(self.offset);
+        match &self.shape {
+            ColliderShape::Sphere { radius } => WorldShape {
+                points: vec![center],
+                radius: radius.abs() * max_scale,
+            },
+            ColliderShape::Aabb { half_extents } => {
+                let half_extents = Vector3(half_extents.0.abs() * scale.0, half_extents.1.abs() * scale.1, half_extents.2.abs() * scale.2);
+                WorldShape {
+                    points: corners(half_extents).into_iter().map(|corner| center + corner).collect(),
+                    radius: 0.0,
+                }
+            },
+            ColliderShape::Obb { half_extents } => WorldShape {
+                points: corners(*half_extents).into_iter().map(|corner| apply(self.offset + corner)).collect(),
+                radius: 0.0,
+            },
+            ColliderShape::Capsule { radius, half_height } => WorldShape {
+                points: vec![
+                    apply(self.offset + Vector3(0.0, *half_height, 0.0)),
+                    apply(self.offset - Vector3(0.0, *half_height, 0.0)),
+                ],
+                radius: radius.abs() * max_scale,
+            },
+            ColliderShape::ConvexHull { points } => WorldShape {
+                points: points.iter().map(|point| apply(self.offset + *point)).collect(),
+                radius: 0.0,
+            },
+        }
+    }
+}
+
+fn corners(half_extents: Vector3<f32>) -> Vec<Vector3<f32>> {
+    let Vector3(x, y, z) = half_extents;
+    vec![
+        Vector3(-x, -y, -z), Vector3(x, -y, -z), Vector3(-x, y, -z), Vector3(x, y, -z),
+        Vector3(-x, -y, z), Vector3(x, -y, z), Vector3(-x, y, z), Vector3(x, y, z),
+    ]
+}
+
+/// \[backend\] A collider in world space as the points it is the convex hull of, grown by a radius.
+#[derive(Debug, Clone)]
+pub(crate) struct WorldShape {
+    pub(crate) points: Vec<Vector3<f32>>,
+    pub(crate) radius: f32,
+}
+
+impl WorldShape {
+    /// Gets the point of the shape furthest in a direction.
+    pub(crate) fn support(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        let furthest = self.points.iter().copied()
+            .max_by(|a, b| Vector3::dot_product(*a, direction).total_cmp(&Vector3::dot_product(*b, direction)))
+            .unwrap();
+        let length = direction.norm();
+        if self.radius > 0.0 && length > f32::EPSILON {
+            furthest + direction * (self.radius / length)
+        } else {
+            furthest
+        }
+    }
+
+    /// Gets the center of the points of the shape.
+    pub(crate) fn center(&self) -> Vector3<f32> {
+        self.points.iter().fold(Vector3(0.0, 0.0, 0.0), |sum, point| sum + *point) * (1.0 / self.points.len() as f32)
+    }
+
+    /// Gets the world-space box containing the shape.
+    pub(crate) fn bounds(&self) -> Aabb {
+        Aabb::from_points(self.points.iter().copied()).unwrap().expand(self.radius)
+    }
+}
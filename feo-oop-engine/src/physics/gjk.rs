@@ -0,0 +1,488 @@
+//! \[backend\] The narrow phase. Finds the contact between two convex shapes.
+//!
+//! GJK finds whether the Minkowski difference of the shapes contains the origin which is the case
+//! when they overlap. EPA then expands the final simplex of GJK towards the surface of the
+//! difference to find the direction and depth of the overlap.
+//!
+//! Rays are cast against a shape with the same support function by moving the start of the ray
+//! forward until the closest point of the shape to it is on its surface.
+//!
+use {
+    super::{
+        Contact,
+        collider::WorldShape,
+    },
+    crate::components::bounds::Ray,
+    feo_math::linear_algebra::vector3::Vector3,
+};
+
+const MAX_ITERATIONS: usize = 64;
+const TOLERANCE: f32 = 1.0e-4;
+const RAY_TOLERANCE: f32 = 1.0e-5; // how far from the surface a ray may stop
+
+/// A point on the Minkowski difference along with the points of the shapes it came from.
+#[derive(Debug, Clone, Copy)]
+struct SupportPoint {
+    point: Vector3<f32>,
+    a: Vector3<f32>,
+    b: Vector3<f32>,
+}
+
+fn support(a: &WorldShape, b: &WorldShape, direction: Vector3<f32>) -> SupportPoint {
+    let on_a = a.support(direction);
+    let on_b = b.support(-direction);
+    SupportPoint {
+        point: on_a - on_b,
+        a: on_a,
+        b: on_b,
+    }
+}
+
+fn dot(a: Vector3<f32>, b: Vector3<f32>) -> f32 { Vector3::dot_product(a, b) }
+
+fn cross(a: Vector3<f32>, b: Vector3<f32>) -> Vector3<f32> { Vector3::cross_product(a, b) }
+
+/// Finds the contact between two shapes. The normal points from a to b. None if they do not overlap.
+pub(crate) fn collide(a: &WorldShape, b: &WorldShape) -> Option<Contact> {
+    if let ([center_a], [center_b]) = (a.points.as_slice(), b.points.as_slice()) {
+        return spheres(*center_a, a.radius, *center_b, b.radius);
+    }
+    let simplex = gjk(a, b)?;
+    epa(a, b, simplex).filter(|contact| contact.depth > TOLERANCE)
+}
+
+/// Spheres are solved exactly as EPA can only approximate their surfaces.
+fn spheres(center_a: Vector3<f32>, radius_a: f32, center_b: Vector3<f32>, radius_b: f32) -> Option<Contact> {
+    let offset = center_b - center_a;
+    let distance = offset.norm();
+    let depth = radius_a + radius_b - distance;
+    if depth <= TOLERANCE {
+        return None;
+    }
+    let normal = if distance <= f32::EPSILON { Vector3(0.0, 1.0, 0.0) } else { offset * (1.0 / distance) };
+    Some(Contact {
+        point: center_a + normal * (radius_a - depth * 0.5),
+        normal,
+        depth,
+    })
+}
+
+fn gjk(a: &WorldShape, b: &WorldShape) -> Option<Vec<SupportPoint>> {
+    let mut direction = b.center() - a.center();
+    if direction.norm() <= f32::EPSILON {
+        direction = Vector3(1.0, 0.0, 0.0);
+    }
+
+    let first = support(a, b, direction);
+    let mut simplex = vec![first];
+    direction = -first.point;
+
+    for _ in 0..MAX_ITERATIONS {
+        if direction.norm() <= f32::EPSILON {
+            // the origin is on the simplex so the shapes touch or overlap. EPA finds out which.
+            return complete_simplex(a, b, simplex);
+        }
+        let next = support(a, b, direction);
+        if dot(next.point, direction) <= 0.0 {
+            return None; // the origin is beyond the furthest point in its direction
+        }
+        simplex.push(next);
+        if next_simplex(&mut simplex, &mut direction) {
+            return Some(simplex);
+        }
+    }
+    None
+}
+
+/// Reduces the simplex to the feature closest to the origin and points the direction at the origin.
+/// Returns true once a tetrahedron contains the origin.
+fn next_simplex(simplex: &mut Vec<SupportPoint>, direction: &mut Vector3<f32>) -> bool {
+    match simplex.len() {
+        2 => {
+            let (b, a) = (simplex[0], simplex[1]);
+            let ab = b.point - a.point;
+            let ao = -a.point;
+            if dot(ab, ao) > 0.0 {
+                *direction = cross(cross(ab, ao), ab);
+            } else {
+                *simplex = vec![a];
+                *direction = ao;
+            }
+            false
+        },
+        3 => {
+            let (c, b, a) = (simplex[0], simplex[1], simplex[2]);
+            triangle(simplex, direction, a, b, c);
+            false
+        },
+        4 => {
+            let (d, c, b, a) = (simplex[0], simplex[1], simplex[2], simplex[3]);
+            let ao = -a.point;
+            let abc = cross(b.point - a.point, c.point - a.point);
+            let acd = cross(c.point - a.point, d.point - a.point);
+            let adb = cross(d.point - a.point, b.point - a.point);
+
+            // whether the origin is beyond a face. Each face normal is pointed away from the opposite vertex.
+            // An origin almost on a face counts as inside as otherwise rounding can make the search cycle.
+            let beyond = |normal: Vector3<f32>, opposite: SupportPoint| {
+                if normal.norm() <= f32::EPSILON {
+                    return false;
+                }
+                let normal = if dot(normal, opposite.point - a.point) > 0.0 { -normal } else { normal };
+                dot(normal.unit_vector(), ao) > TOLERANCE
+            };
+            if beyond(abc, d) {
+                triangle(simplex, direction, a, b, c);
+                false
+            } else if beyond(acd, b) {
+                triangle(simplex, direction, a, c, d);
+                false
+            } else if beyond(adb, c) {
+                triangle(simplex, direction, a, d, b);
+                false
+            } else {
+                true
+            }
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// Grows a simplex into a tetrahedron by searching in directions away from it. None if the difference is flat.
+fn complete_simplex(a: &WorldShape, b: &WorldShape, mut simplex: Vec<SupportPoint>) -> Option<Vec<SupportPoint>> {
+    let axes = [Vector3(1.0, 0.0, 0.0), Vector3(0.0, 1.0, 0.0), Vector3(0.0, 0.0, 1.0)];
+    while simplex.len() < 4 {
+        let directions: Vec<Vector3<f32>> = match simplex.len() {
+            1 => axes.to_vec(),
+            2 => {
+                let line = simplex[1].point - simplex[0].point;
+                axes.iter().map(|axis| cross(line, *axis)).collect()
+            },
+            _ => vec![cross(simplex[1].point - simplex[0].point, simplex[2].point - simplex[0].point)],
+        };
+
+        let next = directions.into_iter()
+            .filter(|direction| direction.norm() > f32::EPSILON)
+            .flat_map(|direction| [direction, -direction])
+            .map(|direction| (direction, support(a, b, direction)))
+            .find(|(direction, next)| dot(next.point - simplex[0].point, direction.unit_vector()) > TOLERANCE)?;
+        simplex.push(next.1);
+    }
+    Some(simplex)
+}
+
+/// Handles a triangle where `a` is the newest point.
+fn triangle(simplex: &mut Vec<SupportPoint>, direction: &mut Vector3<f32>, a: SupportPoint, b: SupportPoint, c: SupportPoint) {
+    let ab = b.point - a.point;
+    let ac = c.point - a.point;
+    let ao = -a.point;
+    let abc = cross(ab, ac);
+
+    if dot(cross(abc, ac), ao) > 0.0 {
+        if dot(ac, ao) > 0.0 {
+            *simplex = vec![c, a];
+            *direction = cross(cross(ac, ao), ac);
+        } else {
+            line(simplex, direction, a, b);
+        }
+    } else if dot(cross(ab, abc), ao) > 0.0 {
+        line(simplex, direction, a, b);
+    } else if dot(abc, ao) > 0.0 {
+        *simplex = vec![c, b, a];
+        *direction = abc;
+    } else {
+        *simplex = vec![b, c, a];
+        *direction = -abc;
+    }
+}
+
+fn line(simplex: &mut Vec<SupportPoint>, direction: &mut Vector3<f32>, a: SupportPoint, b: SupportPoint) {
+    let ab = b.point - a.point;
+    let ao = -a.point;
+    if dot(ab, ao) > 0.0 {
+        *simplex = vec![b, a];
+        *direction = cross(cross(ab, ao), ab);
+    } else {
+        *simplex = vec![a];
+        *direction = ao;
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    vertices: [usize; 3],
+    normal: Vector3<f32>,
+    distance: f32,
+}
+
+fn face(vertices: &[SupportPoint], inside: Vector3<f32>, i: usize, j: usize, k: usize) -> Option<Face> {
+    let normal = cross(vertices[j].point - vertices[i].point, vertices[k].point - vertices[i].point);
+    if normal.norm() <= f32::EPSILON {
+        return None; // degenerate
+    }
+    let normal = normal.unit_vector();
+
+    // outward normals point away from the inside of the polytope
+    let (vertices_order, normal) = if dot(normal, vertices[i].point - inside) < 0.0 {
+        ([i, k, j], -normal)
+    } else {
+        ([i, j, k], normal)
+    };
+    Some(Face {
+        vertices: vertices_order,
+        normal,
+        distance: dot(normal, vertices[i].point).max(0.0),
+    })
+}
+
+fn epa(a: &WorldShape, b: &WorldShape, simplex: Vec<SupportPoint>) -> Option<Contact> {
+    let mut vertices = simplex;
+
+    // the polytope only grows so the center of the tetrahedron stays inside it
+    let inside = vertices.iter().fold(Vector3(0.0, 0.0, 0.0), |sum, vertex| sum + vertex.point) * 0.25;
+    let mut faces: Vec<Face> = [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]].iter()
+        .filter_map(|[i, j, k]| face(&vertices, inside, *i, *j, *k))
+        .collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        let closest = *faces.iter().min_by(|f, g| f.distance.total_cmp(&g.distance))?;
+        let next = support(a, b, closest.normal);
+
+        if dot(next.point, closest.normal) - closest.distance < TOLERANCE {
+            return Some(contact(&vertices, &closest));
+        }
+
+        // remove every face the new point can see and keep the edges bordering the hole
+        vertices.push(next);
+        let new = vertices.len() - 1;
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        faces.retain(|face| {
+            if dot(face.normal, next.point - vertices[face.vertices[0]].point) > f32::EPSILON {
+                let [i, j, k] = face.vertices;
+                for (from, to) in [(i, j), (j, k), (k, i)] {
+                    match edges.iter().position(|&(x, y)| x == to && y == from) {
+                        Some(shared) => { edges.swap_remove(shared); },
+                        None => edges.push((from, to)),
+                    }
+                }
+                false
+            } else {
+                true
+            }
+        });
+        faces.extend(edges.into_iter().filter_map(|(from, to)| face(&vertices, inside, from, to, new)));
+    }
+
+    // did not converge so use the best estimate
+    let closest = *faces.iter().min_by(|f, g| f.distance.total_cmp(&g.distance))?;
+    Some(contact(&vertices, &closest))
+}
+
+/// Builds the contact from the face of the polytope closest to the origin.
+fn contact(vertices: &[SupportPoint], face: &Face) -> Contact {
+    let [p, q, r] = face.vertices.map(|i| vertices[i]);
+
+    // barycentric coordinates of the closest point to the origin on the face
+    let closest = face.normal * face.distance;
+    let (v0, v1, v2) = (q.point - p.point, r.point - p.point, closest - p.point);
+    let (d00, d01, d11) = (dot(v0, v0), dot(v0, v1), dot(v1, v1));
+    let (d20, d21) = (dot(v2, v0), dot(v2, v1));
+    let denominator = d00 * d11 - d01 * d01;
+    let (v, w) = if denominator.abs() <= f32::EPSILON {
+        (0.0, 0.0)
+    } else {
+        ((d11 * d20 - d01 * d21) / denominator, (d00 * d21 - d01 * d20) / denominator)
+    };
+    let u = 1.0 - v - w;
+
+    let on_a = p.a * u + q.a * v + r.a * w;
+    let on_b = p.b * u + q.b * v + r.b * w;
+    Contact {
+        point: (on_a + on_b) * 0.5,
+        normal: face.normal,
+        depth: face.distance,
+    }
+}
+
+/// Casts a ray against a shape. Gets the distance along the ray and the unit normal of the surface where it hits.
+/// A ray starting within the shape hits it at 0 with the normal facing back along the ray.
+pub(crate) fn raycast(shape: &WorldShape, ray: &Ray, max_distance: f32) -> Option<(f32, Vector3<f32>)> {
+    let mut distance = 0.0;
+    let mut start = ray.origin;
+    let mut normal = Vector3(0.0, 0.0, 0.0);
+
+    // the simplex is made of the points start - p for each support point p
+    let mut points: Vec<Vector3<f32>> = Vec::new();
+    let mut closest = start - shape.center();
+    for _ in 0..MAX_ITERATIONS {
+        if closest.norm() <= RAY_TOLERANCE {
+            break; // the start is on the surface
+        }
+        let next = shape.support(closest);
+        let offset = start - next;
+        let progress = dot(closest, offset);
+        if progress > 0.0 {
+            // the shape is entirely beyond a plane through the start so the start can move up to it
+            let approach = dot(closest, ray.direction);
+            if approach >= 0.0 {
+                return None; // pointing away
+            }
+            distance -= progress / approach;
+            if distance > max_distance {
+                return None;
+            }
+            start = ray.at(distance);
+            normal = closest;
+        } else if dot(closest, closest) - progress <= RAY_TOLERANCE * closest.norm() {
+            break; // no closer point exists
+        }
+
+        if !points.contains(&next) {
+            points.push(next);
+        } else if progress <= 0.0 {
+            break; // the simplex can not get any closer
+        }
+        let (point, kept) = closest_on_simplex(&points.iter().map(|point| start - *point).collect::<Vec<_>>());
+        points = kept.into_iter().map(|i| points[i]).collect();
+        closest = point;
+    }
+
+    if normal.norm() <= f32::EPSILON {
+        Some((0.0, -ray.direction))
+    } else {
+        Some((distance, normal.unit_vector()))
+    }
+}
+
+/// Finds the point closest to the origin within the convex hull of up to 4 points and the points needed to describe it.
+fn closest_on_simplex(points: &[Vector3<f32>]) -> (Vector3<f32>, Vec<usize>) {
+    let mut best: Option<(Vector3<f32>, Vec<usize>)> = None;
+    for subset in 1..(1_usize << points.len()) {
+        let indices: Vec<usize> = (0..points.len()).filter(|i| subset & (1 << i) != 0).collect();
+        let weights = match affine_weights(&indices.iter().map(|i| points[*i]).collect::<Vec<_>>()) {
+            Some(weights) if weights.iter().all(|weight| *weight >= -f32::EPSILON) => weights,
+            _ => continue, // the closest point of the subset is outside of it
+        };
+        let point = indices.iter().zip(weights.iter())
+            .fold(Vector3(0.0, 0.0, 0.0), |sum, (i, weight)| sum + points[*i] * *weight);
+        if best.as_ref().is_none_or(|(best, _)| point.norm() < best.norm()) {
+            best = Some((point, indices));
+        }
+    }
+    best.unwrap_or_else(|| (points[0], vec![0]))
+}
+
+/// Gets the weights of the point closest to the origin on the line, plane or space through the points. None if they are degenerate.
+fn affine_weights(points: &[Vector3<f32>]) -> Option<Vec<f32>> {
+    // in double precision as the simplex is nearly flat once the ray is close to the surface
+    let base = [points[0].0 as f64, points[0].1 as f64, points[0].2 as f64];
+    let edges: Vec<[f64; 3]> = points[1..].iter()
+        .map(|point| [point.0 as f64 - base[0], point.1 as f64 - base[1], point.2 as f64 - base[2]])
+        .collect();
+    let dot = |a: &[f64; 3], b: &[f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let n = edges.len();
+
+    // solve the normal equations with gaussian elimination
+    let mut rows: Vec<Vec<f64>> = (0..n).map(|i| {
+        let mut row: Vec<f64> = (0..n).map(|j| dot(&edges[i], &edges[j])).collect();
+        row.push(-dot(&edges[i], &base));
+        row
+    }).collect();
+    for column in 0..n {
+        let pivot = (column..n).max_by(|a, b| rows[*a][column].abs().total_cmp(&rows[*b][column].abs()))?;
+        if rows[pivot][column].abs() <= 1.0e-12 * dot(&edges[column], &edges[column]).max(f64::EPSILON) {
+            return None;
+        }
+        rows.swap(column, pivot);
+        let pivot_row = rows[column].clone();
+        rows.iter_mut().enumerate().filter(|(row, _)| *row != column).for_each(|(_, row)| {
+            let factor = row[column] / pivot_row[column];
+            row.iter_mut().zip(pivot_row.iter()).skip(column).for_each(|(value, pivot)| *value -= factor * pivot);
+        });
+    }
+
+    let weights: Vec<f64> = (0..n).map(|i| rows[i][n] / rows[i][i]).collect();
+    let mut all = vec![1.0 - weights.iter().sum::<f64>()];
+    all.extend(weights);
+    Some(all.into_iter().map(|weight| weight as f32).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::physics::collider::{Collider, ColliderShape},
+        feo_math::utils::space::Space,
+    };
+
+    fn shape_at(shape: ColliderShape, position: Vector3<f32>) -> WorldShape {
+        Collider::new(shape).to_world(&Space::new(Some(position), None, None))
+    }
+
+    fn assert_close(a: Vector3<f32>, b: Vector3<f32>, tolerance: f32) {
+        assert!((a - b).norm() <= tolerance, "{:?} is not {:?}", a, b);
+    }
+
+    #[test]
+    fn sphere_sphere_contact() {
+        let a = shape_at(ColliderShape::Sphere { radius: 1.0 }, Vector3(0.0, 0.0, 0.0));
+        let b = shape_at(ColliderShape::Sphere { radius: 1.0 }, Vector3(1.5, 0.0, 0.0));
+        let contact = collide(&a, &b).unwrap();
+        assert!((contact.depth - 0.5).abs() <= 1.0e-5);
+        assert_close(contact.normal, Vector3(1.0, 0.0, 0.0), 1.0e-5);
+
+        let c = shape_at(ColliderShape::Sphere { radius: 1.0 }, Vector3(2.5, 0.0, 0.0));
+        assert!(collide(&a, &c).is_none());
+    }
+
+    #[test]
+    fn box_box_contact() {
+        let half_extents = Vector3(1.0, 1.0, 1.0);
+        let a = shape_at(ColliderShape::Obb { half_extents }, Vector3(0.0, 0.0, 0.0));
+        let b = shape_at(ColliderShape::Obb { half_extents }, Vector3(1.75, 0.25, 0.0));
+        let contact = collide(&a, &b).unwrap();
+        assert!((contact.depth - 0.25).abs() <= 1.0e-3, "depth {}", contact.depth);
+        assert_close(contact.normal, Vector3(1.0, 0.0, 0.0), 1.0e-3);
+
+        let c = shape_at(ColliderShape::Obb { half_extents }, Vector3(0.0, 2.5, 0.0));
+        assert!(collide(&a, &c).is_none());
+    }
+
+    #[test]
+    fn ray_against_sphere() {
+        let sphere = shape_at(ColliderShape::Sphere { radius: 1.0 }, Vector3(0.0, 0.0, 0.0));
+        let ray = Ray::new(Vector3(-5.0, 0.0, 0.0), Vector3(1.0, 0.0, 0.0)).unwrap();
+        let (distance, normal) = raycast(&sphere, &ray, 100.0).unwrap();
+        assert!((distance - 4.0).abs() <= 1.0e-3, "distance {}", distance);
+        assert_close(normal, Vector3(-1.0, 0.0, 0.0), 1.0e-3);
+
+        assert!(raycast(&sphere, &ray, 3.0).is_none());
+        let away = Ray::new(Vector3(-5.0, 0.0, 0.0), Vector3(-1.0, 0.0, 0.0)).unwrap();
+        assert!(raycast(&sphere, &away, 100.0).is_none());
+    }
+
+    #[test]
+    fn ray_against_box() {
+        let cube = shape_at(ColliderShape::Obb { half_extents: Vector3(1.0, 1.0, 1.0) }, Vector3(0.0, 0.0, 0.0));
+        let ray = Ray::new(Vector3(0.5, -5.0, 0.25), Vector3(0.0, 1.0, 0.0)).unwrap();
+        let (distance, normal) = raycast(&cube, &ray, 100.0).unwrap();
+        assert!((distance - 4.0).abs() <= 1.0e-3, "distance {}", distance);
+        assert_close(normal, Vector3(0.0, -1.0, 0.0), 1.0e-3);
+
+        let past = Ray::new(Vector3(2.0, -5.0, 0.0), Vector3(0.0, 1.0, 0.0)).unwrap();
+        assert!(raycast(&cube, &past, 100.0).is_none());
+    }
+
+    #[test]
+    fn closest_point_on_simplex() {
+        // the closest point of a segment is within it
+        let (point, kept) = closest_on_simplex(&[Vector3(1.0, -1.0, 0.0), Vector3(1.0, 1.0, 0.0)]);
+        assert_close(point, Vector3(1.0, 0.0, 0.0), 1.0e-6);
+        assert_eq!(kept, vec![0, 1]);
+
+        // the closest point of a triangle is one of its corners
+        let (point, kept) = closest_on_simplex(&[Vector3(1.0, 1.0, 0.0), Vector3(2.0, 1.0, 0.0), Vector3(1.0, 2.0, 0.0)]);
+        assert_close(point, Vector3(1.0, 1.0, 0.0), 1.0e-6);
+        assert_eq!(kept, vec![0]);
+    }
+}
@@ -0,0 +1,160 @@
+//! Joints that hold two GameObjects together.
+//!
+//! A joint is attached between two GameObjects with `Scene::set_joint`. At least one of them should have a
+//! rigid body. A GameObject without one is held in place so joining a body to it pins the body to the world.
+//! Joints are solved in the physics step along with the contacts so the bodies they hold still collide with
+//! everything else. Joined GameObjects do not collide with each other unless the joint allows it.
+//!
+//! The anchors of a joint are points in the space of each GameObject. They are turned with their GameObject
+//! but not scaled by it. The angles of hinges are measured from the rotation the GameObjects had when joined.
+//!
+//! ## Example
+//! A door hinged to its frame that swings open by at most 90 degrees
+//! ```no_run
+//! # use feo_oop_engine::physics::joint::{Joint, JointKind};
+//! # use feo_math::linear_algebra::vector3::Vector3;
+//! # fn f(frame: std::sync::Arc<std::sync::RwLock<dyn feo_oop_engine::scene::game_object::GameObject>>, door: std::sync::Arc<std::sync::RwLock<dyn feo_oop_engine::scene::game_object::GameObject>>, engine_globals: feo_oop_engine::scripting::globals::EngineGlobals) {
+//! let (frame, door) = (frame.read().unwrap().get_id(), door.read().unwrap().get_id());
+//! let hinge = Joint::new(JointKind::Hinge {
+//!     axis: Vector3(0.0, 1.0, 0.0),
+//!     limits: Some((0.0, std::f32::consts::FRAC_PI_2)),
+//!     motor: None,
+//! }).with_anchors(Vector3(-0.5, 0.0, 0.0), Vector3(-0.5, 0.0, 0.0));
+//! engine_globals.scene.read().unwrap().set_joint(frame, door, hinge).unwrap();
+//! # }
+//! ```
+//!
+use {
+    crate::{
+        scene::Scene,
+        registration::id::ID,
+    },
+    feo_math::{
+        linear_algebra::vector3::Vector3,
+        rotation::quaternion::Quaternion,
+    },
+};
+
+/// Drives a joint at a speed with a limited strength.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motor {
+    pub speed: f32, // radians per second for hinges and meters per second for sliders
+    pub max_force: f32, // the strongest torque or force it pushes with
+}
+
+/// How a joint lets its GameObjects move relative to each other.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JointKind {
+    /// Holds the GameObjects together as if they were one.
+    Fixed,
+    /// Holds the anchors together and lets the GameObjects turn freely around them.
+    BallSocket,
+    /// Holds the anchors together and lets the GameObjects turn around an axis in the space of the first.
+    /// The limits are the lowest and highest angles in radians.
+    Hinge { axis: Vector3<f32>, limits: Option<(f32, f32)>, motor: Option<Motor> },
+    /// Lets the second GameObject slide along an axis in the space of the first without turning.
+    /// The limits are the lowest and highest distances between the anchors along the axis.
+    Slider { axis: Vector3<f32>, limits: Option<(f32, f32)>, motor: Option<Motor> },
+    /// Pulls the anchors towards a distance apart. A stiffness and damping of 0 leave them free within the limits
+    /// which are the shortest and longest distances between the anchors. e.g. a rope only has a longest distance.
+    Spring { rest_length: f32, stiffness: f32, damping: f32, limits: Option<(f32, f32)> },
+}
+
+/// A joint between two GameObjects. See `Scene::set_joint`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Joint {
+    pub kind: JointKind,
+    pub anchor_a: Vector3<f32>, // in the space of the first GameObject
+    pub anchor_b: Vector3<f32>, // in the space of the second GameObject
+    pub collide_connected: bool, // whether the joined GameObjects still collide with each other
+
+    pub(crate) reference: Quaternion<f32>, // the rotation of the second GameObject relative to the first when they were joined
+}
+
+impl Joint {
+    /// Creates a joint anchored at the origins of both GameObjects.
+    pub fn new(kind: JointKind) -> Self {
+        Joint {
+            kind,
+            anchor_a: Vector3(0.0, 0.0, 0.0),
+            anchor_b: Vector3(0.0, 0.0, 0.0),
+            collide_connected: false,
+            reference: Quaternion::f32_identity(),
+        }
+    }
+
+    /// Moves the anchors within the spaces of the GameObjects.
+    /// # Arguments
+    /// * `anchor_a` - The anchor in the space of the first GameObject.
+    /// * `anchor_b` - The anchor in the space of the second GameObject.
+    pub fn with_anchors(mut self, anchor_a: Vector3<f32>, anchor_b: Vector3<f32>) -> Self {
+        self.anchor_a = anchor_a;
+        self.anchor_b = anchor_b;
+        self
+    }
+}
+
+impl Scene {
+    /// Joins two GameObjects. Replaces any joint already between them.
+    ///
+    /// Do not call it while holding a write lock on either GameObject.
+    /// # Arguments
+    /// * `a` - The ID of the first GameObject.
+    /// * `b` - The ID of the second GameObject.
+    /// * `joint` - The joint.
+    pub fn set_joint(&self, a: ID, b: ID, mut joint: Joint) -> Result<(), &'static str> {
+        if a == b {
+            return Err("A GameObject can not be joined to itself.");
+        }
+        let rotation = |id: &ID| self.find_by_id(id)
+            .map(|game_object| game_object.read().unwrap().world_rotation().unit_quaternion())
+            .ok_or("No GameObject with this ID is in the scene.");
+        joint.reference = (rotation(&a)?.reciprocal() * rotation(&b)?).unit_quaternion();
+
+        let mut joints = self.physics.joints.lock().unwrap();
+        joints.remove(&(b.clone(), a.clone()));
+        joints.insert((a, b), joint);
+        Ok(())
+    }
+
+    /// Removes the joint between two GameObjects in either order.
+    /// # Arguments
+    /// * `a` - The ID of one GameObject.
+    /// * `b` - The ID of the other GameObject.
+    pub fn remove_joint(&self, a: &ID, b: &ID) -> Option<Joint> {
+        let mut joints = self.physics.joints.lock().unwrap();
+        joints.remove(&(a.clone(), b.clone())).or_else(|| joints.remove(&(b.clone(), a.clone())))
+    }
+
+    /// Gets a copy of the joint between two GameObjects in either order.
+    /// # Arguments
+    /// * `a` - The ID of one GameObject.
+    /// * `b` - The ID of the other GameObject.
+    pub fn get_joint(&self, a: &ID, b: &ID) -> Option<Joint> {
+        let joints = self.physics.joints.lock().unwrap();
+        joints.get(&(a.clone(), b.clone())).or_else(|| joints.get(&(b.clone(), a.clone()))).cloned()
+    }
+
+    /// Changes the joint between two GameObjects in place. e.g. to turn on its motor. Fails if they are not joined.
+    /// # Arguments
+    /// * `a` - The ID of one GameObject.
+    /// * `b` - The ID of the other GameObject.
+    /// * `f` - The function changing the joint.
+    pub fn modify_joint<F: FnOnce(&mut Joint)>(&self, a: &ID, b: &ID, f: F) -> Result<(), &'static str> {
+        let mut joints = self.physics.joints.lock().unwrap();
+        let key = if joints.contains_key(&(a.clone(), b.clone())) { (a.clone(), b.clone()) } else { (b.clone(), a.clone()) };
+        let joint = joints.get_mut(&key).ok_or("These GameObjects are not joined.")?;
+        f(joint);
+        Ok(())
+    }
+
+    /// Gets the IDs of the GameObjects joined to a GameObject.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn get_joined(&self, id: &ID) -> Vec<ID> {
+        self.physics.joints.lock().unwrap().keys()
+            .filter(|(a, b)| a.is_alive() && b.is_alive())
+            .filter_map(|(a, b)| if a == id { Some(b.clone()) } else if b == id { Some(a.clone()) } else { None })
+            .collect()
+    }
+}
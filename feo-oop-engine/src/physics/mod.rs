@@ -0,0 +1,406 @@
+//! Collision detection and rigid body dynamics for GameObjects.
+//!
+//! A GameObject collides once a collider is attached to it with `Scene::set_collider`. The colliders
+//! are kept by the scene so any GameObject can be given one. Every frame before the scripts run the
+//! colliders are moved into world space, the pairs whose bounds overlap are found by sweeping along
+//! the x axis and the exact contact of each pair is found with GJK and EPA.
+//!
+//! Both GameObjects of a colliding pair receive a `UserEvent::Collision` through their own event
+//! handler for every frame they overlap. Colliders of GameObjects that are inactive in the hierarchy
+//! are ignored.
+//!
+//! GameObjects given a rigid body with `Scene::set_rigid_body` are moved by the physics step. It runs on a
+//! fixed timestep so the motion does not depend on the frame rate. Each step applies gravity and the
+//! forces of the scripts, bounces and slides colliding bodies off each other and writes the new positions
+//! and rotations back to the subspaces of the GameObjects. Colliders without a rigid body do not move.
+//!
+//! Rigid bodies can be held together by joints such as hinges and springs with `Scene::set_joint`. See `joint`.
+//!
+//! GameObjects walked around by the scripts are better given a character controller with
+//! `Scene::set_character_controller`. It slides along the colliders in its way instead of passing through them.
+//! See `character`.
+//!
+//! ## Example
+//! ```no_run
+//! # use feo_oop_engine::physics::collider::{Collider, ColliderShape};
+//! # fn f(ball: std::sync::Arc<std::sync::RwLock<dyn feo_oop_engine::scene::game_object::GameObject>>, engine_globals: feo_oop_engine::scripting::globals::EngineGlobals) {
+//! let id = ball.read().unwrap().get_id();
+//! engine_globals.scene.read().unwrap().set_collider(id, Collider::new(ColliderShape::Sphere { radius: 0.5 })).unwrap();
+//! # }
+//! ```
+//! Then within the event handler of the ball
+//! ```ignore
+//! if let Event::UserEvent(UserEvent::Collision(_this, other, contact)) = event {
+//!     println!("hit {} at {:?}", other.read().unwrap().get_name(), contact.point);
+//! }
+//! ```
+//! A falling and bouncing ball
+//! ```no_run
+//! # use feo_oop_engine::physics::{collider::{Collider, ColliderShape}, rigid_body::RigidBody};
+//! # fn f(ball: std::sync::Arc<std::sync::RwLock<dyn feo_oop_engine::scene::game_object::GameObject>>, engine_globals: feo_oop_engine::scripting::globals::EngineGlobals) {
+//! let id = ball.read().unwrap().get_id();
+//! let scene = engine_globals.scene.read().unwrap();
+//! let shape = ColliderShape::Sphere { radius: 0.5 };
+//! let mut body = RigidBody::new(1.0).with_inertia_of(&shape);
+//! body.restitution = 0.8;
+//! scene.set_rigid_body(id.clone(), body);
+//! scene.set_collider(id, Collider::new(shape)).unwrap();
+//! # }
+//! ```
+//!
+pub mod collider;
+pub mod rigid_body;
+pub mod trigger;
+pub mod raycast;
+pub mod character;
+pub mod joint;
+mod gjk;
+mod solver;
+
+use {
+    collider::{Collider, WorldShape},
+    rigid_body::RigidBody,
+    trigger::{Trigger, Overlap},
+    character::CharacterController,
+    joint::Joint,
+    crate::{
+        scene::{
+            Scene,
+            game_object::GameObject,
+        },
+        registration::id::ID,
+        components::bounds::Aabb,
+        scripting::executor::Spawner,
+        event::UserEvent,
+    },
+    feo_math::linear_algebra::vector3::Vector3,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex, RwLock},
+        time::Instant,
+    },
+    winit::event::Event,
+};
+
+/// Where and how deeply two colliders overlap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    pub point: Vector3<f32>, // in world space
+    pub normal: Vector3<f32>, // unit length. Points from the GameObject receiving the event to the other.
+    pub depth: f32,
+}
+
+impl Contact {
+    /// Gets the contact as seen from the other GameObject.
+    pub fn flipped(&self) -> Self {
+        Contact {
+            point: self.point,
+            normal: -self.normal,
+            depth: self.depth,
+        }
+    }
+}
+
+/// Two GameObjects whose colliders overlap and their contact. The normal points from the first to the second.
+pub type CollisionPair = (Arc<RwLock<dyn GameObject>>, Arc<RwLock<dyn GameObject>>, Contact);
+
+/// The most steps run in a frame. Slower frames slow down the simulation instead of taking ever longer.
+const MAX_STEPS: usize = 5;
+
+/// \[backend\] The physics state of a scene.
+#[derive(Default)]
+pub(crate) struct PhysicsWorld {
+    colliders: Mutex<HashMap<ID, Collider>>,
+    rigid_bodies: Mutex<HashMap<ID, RigidBody>>,
+    triggers: Mutex<HashMap<ID, Trigger>>,
+    overlaps: Mutex<HashMap<(ID, ID), Overlap>>, // the GameObjects within each trigger on the last frame
+    characters: Mutex<HashMap<ID, CharacterController>>,
+    joints: Mutex<HashMap<(ID, ID), Joint>>,
+    settings: Mutex<Settings>,
+    clock: Mutex<Clock>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+    gravity: Vector3<f32>,
+    timestep: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            gravity: Vector3(0.0, -9.81, 0.0),
+            timestep: 1.0 / 60.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Clock {
+    last_step: Option<Instant>,
+    accumulator: f32, // the time not yet simulated
+}
+
+/// A collider in world space along with its GameObject.
+struct Body {
+    game_object: Arc<RwLock<dyn GameObject>>,
+    shape: WorldShape,
+}
+
+impl PhysicsWorld {
+    /// Moves the colliders and character controllers of the active GameObjects into world space. Those of dead GameObjects are dropped.
+    fn bodies(&self, scene: &Scene) -> Vec<Body> {
+        let colliders: Vec<(ID, Collider)> = {
+            let mut colliders = self.colliders.lock().unwrap();
+            colliders.retain(|id, _| id.is_alive());
+            colliders.iter().map(|(id, collider)| (id.clone(), collider.clone())).collect()
+        };
+
+        colliders.into_iter().filter_map(|(id, collider)| {
+            let game_object = scene.find_by_id(&id)?;
+            let shape = {
+                let read = game_object.read().unwrap();
+                if !read.is_active_in_hierarchy() {
+                    return None;
+                }
+                collider.to_world(&read.get_subspace())
+            };
+            Some(Body { game_object, shape })
+        }).chain(self.character_bodies(scene)).collect()
+    }
+
+    /// Finds every pair of overlapping colliders. Joined GameObjects do not collide unless their joint allows it.
+    fn contacts(&self, scene: &Scene) -> Vec<CollisionPair> {
+        let bodies = self.bodies(scene);
+        let joined: Vec<(ID, ID)> = self.joints.lock().unwrap().iter()
+            .filter(|(_, joint)| !joint.collide_connected)
+            .map(|(pair, _)| pair.clone())
+            .collect();
+
+        // sweep and prune along the x axis
+        let mut order: Vec<(usize, Aabb)> = bodies.iter().enumerate()
+            .map(|(i, body)| (i, body.shape.bounds()))
+            .collect();
+        order.sort_by(|a, b| a.1.min.0.total_cmp(&b.1.min.0));
+
+        let mut contacts = Vec::new();
+        for (n, (i, bounds)) in order.iter().enumerate() {
+            for (j, other) in order[n + 1..].iter() {
+                if other.min.0 > bounds.max.0 {
+                    break;
+                }
+                if !bounds.intersects(other) {
+                    continue;
+                }
+                let (a, b) = (&bodies[*i], &bodies[*j]);
+                if Arc::ptr_eq(&a.game_object, &b.game_object) {
+                    continue; // a character controller and a collider of the same GameObject
+                }
+                if !joined.is_empty() {
+                    let (id_a, id_b) = (a.game_object.read().unwrap().get_id(), b.game_object.read().unwrap().get_id());
+                    if joined.iter().any(|(x, y)| (*x == id_a && *y == id_b) || (*x == id_b && *y == id_a)) {
+                        continue;
+                    }
+                }
+                if let Some(contact) = gjk::collide(&a.shape, &b.shape) {
+                    contacts.push((a.game_object.clone(), b.game_object.clone(), contact));
+                }
+            }
+        }
+        contacts
+    }
+
+    /// Runs as many fixed steps as fit in the time since the last frame.
+    fn advance(&self, scene: &Scene) {
+        let settings = *self.settings.lock().unwrap();
+        let steps = {
+            let mut clock = self.clock.lock().unwrap();
+            let now = Instant::now();
+            let delta = clock.last_step
+                .replace(now)
+                .map_or(0.0, |last_step| (now - last_step).as_secs_f32());
+            clock.accumulator = (clock.accumulator + delta).min(settings.timestep * MAX_STEPS as f32);
+            let steps = (clock.accumulator / settings.timestep) as usize;
+            clock.accumulator -= steps as f32 * settings.timestep;
+            steps
+        };
+
+        for _ in 0..steps {
+            solver::step(self, scene, settings.timestep, settings.gravity);
+            self.move_characters(scene, settings.timestep, settings.gravity);
+        }
+        // forces last a frame even if no step ran so that forces applied every frame do not add up
+        self.rigid_bodies.lock().unwrap().values_mut().for_each(|body| body.clear_forces());
+    }
+
+    /// Restarts the clock so the time a scene spent inactive is not simulated.
+    pub(crate) fn reset_clock(&self) {
+        *self.clock.lock().unwrap() = Clock::default();
+    }
+}
+
+impl Clone for PhysicsWorld {
+    fn clone(&self) -> Self {
+        PhysicsWorld {
+            colliders: Mutex::new(self.colliders.lock().unwrap().clone()),
+            rigid_bodies: Mutex::new(self.rigid_bodies.lock().unwrap().clone()),
+            triggers: Mutex::new(self.triggers.lock().unwrap().clone()),
+            overlaps: Mutex::default(), // the copy has GameObjects of its own
+            characters: Mutex::new(self.characters.lock().unwrap().clone()),
+            joints: Mutex::new(self.joints.lock().unwrap().clone()),
+            settings: Mutex::new(*self.settings.lock().unwrap()),
+            clock: Mutex::new(*self.clock.lock().unwrap()),
+        }
+    }
+}
+
+impl std::fmt::Debug for PhysicsWorld {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PhysicsWorld")
+            .field("colliders", &self.colliders.lock().unwrap().len())
+            .field("rigid_bodies", &self.rigid_bodies.lock().unwrap().len())
+            .field("triggers", &self.triggers.lock().unwrap().len())
+            .field("characters", &self.characters.lock().unwrap().len())
+            .field("joints", &self.joints.lock().unwrap().len())
+            .field("settings", &*self.settings.lock().unwrap()).finish()
+    }
+}
+
+impl Scene {
+    /// Attaches a collider to a GameObject. Replaces any collider it already has.
+    /// Fails if the shape has no size. See `ColliderShape::validate`.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `collider` - The collider.
+    pub fn set_collider(&self, id: ID, collider: Collider) -> Result<(), &'static str> {
+        collider.shape.validate()?;
+        self.physics.colliders.lock().unwrap().insert(id, collider);
+        Ok(())
+    }
+
+    /// Removes the collider of a GameObject.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn remove_collider(&self, id: &ID) -> Option<Collider> {
+        self.physics.colliders.lock().unwrap().remove(id)
+    }
+
+    /// Gets the collider of a GameObject.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn get_collider(&self, id: &ID) -> Option<Collider> {
+        self.physics.colliders.lock().unwrap().get(id).cloned()
+    }
+
+    /// Finds every pair of GameObjects whose colliders overlap.
+    ///
+    /// Do not call it while holding a write lock on a GameObject with a collider.
+    pub fn find_collisions(&self) -> Vec<CollisionPair> {
+        self.physics.contacts(self)
+    }
+
+    /// \[backend\] Sends a `Collision` event to both GameObjects of every overlapping pair. Run once per frame.
+    pub fn detect_collisions(&self, spawner: Spawner) {
+        self.find_collisions().into_iter().for_each(|(a, b, contact)| {
+            let event = Event::UserEvent(UserEvent::Collision(a.clone(), b.clone(), contact));
+            a.write().unwrap().spawn_own_script_handler(a.clone(), spawner.clone(), event);
+
+            let event = Event::UserEvent(UserEvent::Collision(b.clone(), a.clone(), contact.flipped()));
+            b.write().unwrap().spawn_own_script_handler(b.clone(), spawner.clone(), event);
+        });
+    }
+
+    /// Attaches a rigid body to a GameObject so that it is moved by the physics step. Replaces any rigid body it already has.
+    ///
+    /// The GameObject only collides with others if it also has a collider.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `body` - The rigid body.
+    pub fn set_rigid_body(&self, id: ID, body: RigidBody) {
+        self.physics.rigid_bodies.lock().unwrap().insert(id, body);
+    }
+
+    /// Removes the rigid body of a GameObject. It stays where it is.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn remove_rigid_body(&self, id: &ID) -> Option<RigidBody> {
+        self.physics.rigid_bodies.lock().unwrap().remove(id)
+    }
+
+    /// Gets a copy of the rigid body of a GameObject.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn get_rigid_body(&self, id: &ID) -> Option<RigidBody> {
+        self.physics.rigid_bodies.lock().unwrap().get(id).cloned()
+    }
+
+    /// Changes the rigid body of a GameObject in place. Fails if it has no rigid body.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `f` - The function changing the rigid body.
+    pub fn modify_rigid_body<F: FnOnce(&mut RigidBody)>(&self, id: &ID, f: F) -> Result<(), &'static str> {
+        let mut bodies = self.physics.rigid_bodies.lock().unwrap();
+        let body = bodies.get_mut(id).ok_or("This GameObject has no rigid body.")?;
+        f(body);
+        Ok(())
+    }
+
+    /// Pushes a GameObject for the next frame. Fails if it has no rigid body. See `RigidBody::apply_force`.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `force` - The force in newtons.
+    pub fn apply_force(&self, id: &ID, force: Vector3<f32>) -> Result<(), &'static str> {
+        self.modify_rigid_body(id, |body| body.apply_force(force))
+    }
+
+    /// Changes the momentum of a GameObject at once. Fails if it has no rigid body. See `RigidBody::apply_impulse`.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `impulse` - The impulse in newton seconds.
+    pub fn apply_impulse(&self, id: &ID, impulse: Vector3<f32>) -> Result<(), &'static str> {
+        self.modify_rigid_body(id, |body| body.apply_impulse(impulse))
+    }
+
+    /// Changes the momentum of a GameObject at once at a point which also turns it. Fails if it has no rigid body.
+    ///
+    /// Do not call it while holding a write lock on the GameObject.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `impulse` - The impulse in newton seconds.
+    /// * `point` - The point in world space the impulse is applied at.
+    pub fn apply_impulse_at(&self, id: &ID, impulse: Vector3<f32>, point: Vector3<f32>) -> Result<(), &'static str> {
+        let position = self.find_by_id(id).ok_or("No GameObject with this ID is in the scene.")?.read().unwrap().world_position();
+        self.modify_rigid_body(id, |body| body.apply_impulse_at(impulse, point - position))
+    }
+
+    /// Sets the acceleration due to gravity of the rigid bodies and character controllers. Defaults to 9.81 downwards along the y axis.
+    pub fn set_gravity(&self, gravity: Vector3<f32>) {
+        self.physics.settings.lock().unwrap().gravity = gravity;
+    }
+
+    /// Gets the acceleration due to gravity of the rigid bodies and character controllers.
+    pub fn get_gravity(&self) -> Vector3<f32> {
+        self.physics.settings.lock().unwrap().gravity
+    }
+
+    /// Sets the length of a physics step in seconds. Defaults to 1/60.
+    pub fn set_fixed_timestep(&self, timestep: f32) -> Result<(), &'static str> {
+        if timestep <= 0.0 || !timestep.is_finite() {
+            return Err("The timestep must be a positive number of seconds.");
+        }
+        self.physics.settings.lock().unwrap().timestep = timestep;
+        Ok(())
+    }
+
+    /// Gets the length of a physics step in seconds.
+    pub fn get_fixed_timestep(&self) -> f32 {
+        self.physics.settings.lock().unwrap().timestep
+    }
+
+    /// \[backend\] Moves the rigid bodies and character controllers by the time since the last frame and sends the `Collision` and trigger events. Run once per frame.
+    pub fn step_physics(&self, spawner: Spawner) {
+        self.physics.advance(self);
+        self.detect_collisions(spawner.clone());
+        self.detect_triggers(spawner);
+    }
+}
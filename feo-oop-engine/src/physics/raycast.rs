@@ -0,0 +1,203 @@
+//! Rays cast against the colliders and triangle meshes of a scene.
+//!
+//! By default rays only hit colliders which is fast as every collider is a convex shape. Rays can also
+//! be cast against the triangles of the meshes of the GameObjects which is exact but slower as every
+//! triangle of the meshes the ray passes near is tested. The meshes are found through the spatial index
+//! of the scene so a GameObject moved within the frame is found at its old position. See `scene::spatial`.
+//! Triggers are never hit and GameObjects that are inactive in the hierarchy are ignored.
+//!
+//! ## Example
+//! ```no_run
+//! # use feo_math::linear_algebra::vector3::Vector3;
+//! # fn f(player: std::sync::Arc<std::sync::RwLock<dyn feo_oop_engine::scene::game_object::GameObject>>, engine_globals: feo_oop_engine::scripting::globals::EngineGlobals) {
+//! let (id, position) = {
+//!     let player = player.read().unwrap();
+//!     (player.get_id(), player.world_position())
+//! };
+//! let ground = engine_globals.scene.read().unwrap().raycast(position, Vector3(0.0, -1.0, 0.0), 1.1, |other| *other != id);
+//! if let Some(hit) = ground {
+//!     println!("standing on {} at {:?}", hit.id, hit.point);
+//! }
+//! # }
+//! ```
+//!
+use {
+    super::gjk,
+    crate::{
+        scene::{
+            Scene,
+            game_object::GameObject,
+        },
+        registration::id::ID,
+        components::bounds::Ray,
+    },
+    feo_math::linear_algebra::vector3::Vector3,
+    std::sync::{Arc, RwLock},
+};
+
+/// Where a ray hit a GameObject.
+#[derive(Debug, Clone)]
+pub struct RaycastHit {
+    pub game_object: Arc<RwLock<dyn GameObject>>,
+    pub id: ID,
+    pub distance: f32, // along the ray
+    pub point: Vector3<f32>, // in world space
+    pub normal: Vector3<f32>, // the unit normal of the surface hit facing the ray
+    pub barycentric: Option<(f32, f32)>, // the weights (u, v) of the second and third vertices of the triangle hit. None for colliders.
+}
+
+/// What rays are cast against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaycastOptions {
+    pub colliders: bool,
+    pub meshes: bool, // the triangles of the meshes of the GameObjects
+}
+
+impl Default for RaycastOptions {
+    fn default() -> Self {
+        RaycastOptions {
+            colliders: true,
+            meshes: false,
+        }
+    }
+}
+
+/// Gets the distance along a ray at which it hits a triangle and the weights of the second and third vertices.
+fn intersect_triangle(ray: &Ray, [a, b, c]: [Vector3<f32>; 3], max_distance: f32) -> Option<(f32, f32, f32)> {
+    // Möller–Trumbore
+    let (ab, ac) = (b - a, c - a);
+    let p = Vector3::cross_product(ray.direction, ac);
+    let determinant = Vector3::dot_product(ab, p);
+    if determinant.abs() <= f32::EPSILON {
+        return None; // parallel to the triangle
+    }
+    let inverse = 1.0 / determinant;
+    let offset = ray.origin - a;
+    let u = Vector3::dot_product(offset, p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = Vector3::cross_product(offset, ab);
+    let v = Vector3::dot_product(ray.direction, q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let distance = Vector3::dot_product(ac, q) * inverse;
+    (0.0..=max_distance).contains(&distance).then_some((distance, u, v))
+}
+
+/// Finds where a ray first hits the triangles of the meshes of a GameObject.
+fn raycast_meshes(game_object: &Arc<RwLock<dyn GameObject>>, ray: &Ray, max_distance: f32) -> Option<RaycastHit> {
+    let (id, meshes, m) = {
+        let read = game_object.read().unwrap();
+        (read.get_id(), read.get_triangle_mesh(), read.get_subspace().build().m)
+    };
+    let apply = |point: Vector3<f32>| Vector3(
+        m[0][0] * point.0 + m[0][1] * point.1 + m[0][2] * point.2 + m[0][3],
+        m[1][0] * point.0 + m[1][1] * point.1 + m[1][2] * point.2 + m[1][3],
+        m[2][0] * point.0 + m[2][1] * point.1 + m[2][2] * point.2 + m[2][3],
+    );
+
+    let mut closest: Option<(f32, f32, f32, Vector3<f32>)> = None;
+    meshes.iter().for_each(|mesh| {
+        let vertices = match mesh.vertex_buffer.as_ref().map(|buffer| buffer.read()) {
+            Some(Ok(vertices)) => vertices,
+            _ => return, // empty or in use by the GPU
+        };
+        vertices.chunks_exact(3).for_each(|triangle| {
+            let corners = [0, 1, 2].map(|i| apply(Vector3::from(triangle[i].position)));
+            let limit = closest.map_or(max_distance, |closest| closest.0);
+            if let Some((distance, u, v)) = intersect_triangle(ray, corners, limit) {
+                let normal = Vector3::cross_product(corners[1] - corners[0], corners[2] - corners[0]).unit_vector();
+                closest = Some((distance, u, v, normal));
+            }
+        });
+    });
+
+    closest.map(|(distance, u, v, normal)| RaycastHit {
+        game_object: game_object.clone(),
+        id,
+        distance,
+        point: ray.at(distance),
+        normal: if Vector3::dot_product(normal, ray.direction) > 0.0 { -normal } else { normal },
+        barycentric: Some((u, v)),
+    })
+}
+
+impl Scene {
+    /// Finds where a ray first hits a collider.
+    ///
+    /// Do not call it while holding a write lock on a GameObject with a collider.
+    /// # Arguments
+    /// * `origin` - The point in world space the ray starts at.
+    /// * `direction` - The direction of the ray.
+    /// * `max_distance` - The distance along the ray after which nothing is hit.
+    /// * `filter` - Returns false for GameObjects to skip. e.g. `|id| *id != my_id`
+    pub fn raycast<F>(&self, origin: Vector3<f32>, direction: Vector3<f32>, max_distance: f32, filter: F) -> Option<RaycastHit>
+    where F: FnMut(&ID) -> bool {
+        self.raycast_with(origin, direction, max_distance, RaycastOptions::default(), filter)
+    }
+
+    /// Finds where a ray first hits a collider or a mesh depending on the options. See `raycast`.
+    pub fn raycast_with<F>(&self, origin: Vector3<f32>, direction: Vector3<f32>, max_distance: f32, options: RaycastOptions, filter: F) -> Option<RaycastHit>
+    where F: FnMut(&ID) -> bool {
+        self.raycast_all(origin, direction, max_distance, options, filter).into_iter().next()
+    }
+
+    /// Finds every GameObject a ray hits along with where it first hits them. Sorted from closest to furthest.
+    ///
+    /// Do not call it while holding a write lock on a GameObject with a collider or a mesh.
+    /// # Arguments
+    /// * `origin` - The point in world space the ray starts at.
+    /// * `direction` - The direction of the ray.
+    /// * `max_distance` - The distance along the ray after which nothing is hit.
+    /// * `options` - Whether to hit colliders, meshes or both.
+    /// * `filter` - Returns false for GameObjects to skip. e.g. `|id| *id != my_id`
+    pub fn raycast_all<F>(&self, origin: Vector3<f32>, direction: Vector3<f32>, max_distance: f32, options: RaycastOptions, mut filter: F) -> Vec<RaycastHit>
+    where F: FnMut(&ID) -> bool {
+        let ray = match Ray::new(origin, direction) {
+            Some(ray) => ray,
+            None => return Vec::new(),
+        };
+
+        let mut hits: Vec<RaycastHit> = Vec::new();
+        if options.colliders {
+            self.physics.bodies(self).into_iter().for_each(|body| {
+                let id = body.game_object.read().unwrap().get_id();
+                if body.shape.bounds().intersect_ray(&ray, max_distance).is_none() || !filter(&id) {
+                    return;
+                }
+                if let Some((distance, normal)) = gjk::raycast(&body.shape, &ray, max_distance) {
+                    hits.push(RaycastHit {
+                        game_object: body.game_object,
+                        id,
+                        distance,
+                        point: ray.at(distance),
+                        normal,
+                        barycentric: None,
+                    });
+                }
+            });
+        }
+        if options.meshes {
+            self.query_ray(&ray, max_distance).into_iter().for_each(|(game_object, _)| {
+                let id = game_object.read().unwrap().get_id();
+                if filter(&id) {
+                    hits.extend(raycast_meshes(&game_object, &ray, max_distance));
+                }
+            });
+        }
+
+        // keep the closest hit of each GameObject
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        let mut seen: Vec<ID> = Vec::new();
+        hits.retain(|hit| {
+            let first = !seen.contains(&hit.id);
+            if first {
+                seen.push(hit.id.clone());
+            }
+            first
+        });
+        hits
+    }
+}
@@ -0,0 +1,172 @@
+//! Rigid bodies that are moved by the physics step.
+//!
+//! A rigid body is attached to a GameObject with `Scene::set_rigid_body`. Its center of mass is the
+//! origin of the GameObject. Velocities, forces and impulses are in world space.
+//!
+use {
+    super::collider::ColliderShape,
+    crate::components::bounds::Aabb,
+    feo_math::{
+        linear_algebra::vector3::Vector3,
+        rotation::quaternion::Quaternion,
+    },
+};
+
+/// The mass and motion of a GameObject.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RigidBody {
+    pub mass: f32, // a mass of 0 or less can not be moved by forces or collisions
+    pub inertia: Vector3<f32>, // the moments of inertia around the axes of the GameObject. 0 stops it rotating around that axis.
+    pub velocity: Vector3<f32>,
+    pub angular_velocity: Vector3<f32>, // radians per second around each axis
+    pub use_gravity: bool,
+    pub friction: f32,
+    pub restitution: f32, // 0 stops on impact, 1 bounces back at the same speed
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub kinematic: bool, // moved by its velocity alone. Pushes dynamic bodies but is not pushed back.
+
+    pub(crate) force: Vector3<f32>,
+    pub(crate) torque: Vector3<f32>,
+    pub(crate) impulse: Vector3<f32>,
+    pub(crate) angular_impulse: Vector3<f32>,
+}
+
+impl RigidBody {
+    /// Creates a dynamic body at rest with the inertia of a solid cube of side 1.
+    /// # Arguments
+    /// * `mass` - The mass in kilograms.
+    pub fn new(mass: f32) -> Self {
+        let moment = mass / 6.0;
+        RigidBody {
+            mass,
+            inertia: Vector3(moment, moment, moment),
+            velocity: Vector3(0.0, 0.0, 0.0),
+            angular_velocity: Vector3(0.0, 0.0, 0.0),
+            use_gravity: true,
+            friction: 0.5,
+            restitution: 0.0,
+            linear_damping: 0.0,
+            angular_damping: 0.05,
+            kinematic: false,
+            force: Vector3(0.0, 0.0, 0.0),
+            torque: Vector3(0.0, 0.0, 0.0),
+            impulse: Vector3(0.0, 0.0, 0.0),
+            angular_impulse: Vector3(0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Creates a body that is only moved by its velocity.
+    pub fn new_kinematic() -> Self {
+        RigidBody {
+            kinematic: true,
+            use_gravity: false,
+            ..RigidBody::new(0.0)
+        }
+    }
+
+    /// Sets the inertia to that of a solid collider shape of the same mass.
+    ///
+    /// The scale of the GameObject is not taken into account. Bodies with an `Aabb` shape do not rotate
+    /// as the box does not rotate with its GameObject.
+    pub fn with_inertia_of(mut self, shape: &ColliderShape) -> Self {
+        let m = self.mass.max(0.0);
+        let cuboid = |Vector3(x, y, z): Vector3<f32>| Vector3(
+            m / 3.0 * (y * y + z * z),
+            m / 3.0 * (x * x + z * z),
+            m / 3.0 * (x * x + y * y),
+        );
+        self.inertia = match shape {
+            ColliderShape::Sphere { radius } => {
+                let moment = 0.4 * m * radius * radius;
+                Vector3(moment, moment, moment)
+            },
+            ColliderShape::Aabb { .. } => Vector3(0.0, 0.0, 0.0),
+            ColliderShape::Obb { half_extents } => cuboid(*half_extents),
+            ColliderShape::Capsule { radius, half_height } => {
+                // a cylinder as long as the capsule
+                let length = 2.0 * (half_height + radius);
+                let across = m / 12.0 * (3.0 * radius * radius + length * length);
+                Vector3(across, 0.5 * m * radius * radius, across)
+            },
+            ColliderShape::ConvexHull { points } => {
+                let bounds = Aabb::from_points(points.iter().copied());
+                cuboid(bounds.map_or(Vector3(0.0, 0.0, 0.0), |bounds| bounds.half_extents()))
+            },
+        };
+        self
+    }
+
+    /// Checks if forces and collisions can move the body.
+    pub fn is_dynamic(&self) -> bool {
+        !self.kinematic && self.mass > 0.0
+    }
+
+    /// Pushes the body for the next frame. Apply it on every frame to keep pushing whatever the frame rate.
+    /// A push on a single frame may fall between physics steps, so use `apply_impulse` for that.
+    /// # Arguments
+    /// * `force` - The force in newtons through the center of mass.
+    pub fn apply_force(&mut self, force: Vector3<f32>) {
+        self.force = self.force + force;
+    }
+
+    /// Pushes the body for the next frame at a point away from the center of mass which also turns it.
+    /// # Arguments
+    /// * `force` - The force in newtons.
+    /// * `offset` - The point the force is applied at relative to the position of the GameObject in world space.
+    pub fn apply_force_at(&mut self, force: Vector3<f32>, offset: Vector3<f32>) {
+        self.force = self.force + force;
+        self.torque = self.torque + Vector3::cross_product(offset, force);
+    }
+
+    /// Turns the body for the next frame.
+    /// # Arguments
+    /// * `torque` - The torque in newton meters around each world axis.
+    pub fn apply_torque(&mut self, torque: Vector3<f32>) {
+        self.torque = self.torque + torque;
+    }
+
+    /// Changes the momentum of the body at once. It takes effect at the next physics step.
+    /// # Arguments
+    /// * `impulse` - The impulse in newton seconds through the center of mass.
+    pub fn apply_impulse(&mut self, impulse: Vector3<f32>) {
+        self.impulse = self.impulse + impulse;
+    }
+
+    /// Changes the momentum of the body at once at a point away from the center of mass. It takes effect at the next physics step.
+    /// # Arguments
+    /// * `impulse` - The impulse in newton seconds.
+    /// * `offset` - The point the impulse is applied at relative to the position of the GameObject in world space.
+    pub fn apply_impulse_at(&mut self, impulse: Vector3<f32>, offset: Vector3<f32>) {
+        self.impulse = self.impulse + impulse;
+        self.angular_impulse = self.angular_impulse + Vector3::cross_product(offset, impulse);
+    }
+
+    /// \[backend\] Gets the inverse of the mass. 0 if the body can not be moved.
+    pub(crate) fn inverse_mass(&self) -> f32 {
+        if self.is_dynamic() { 1.0 / self.mass } else { 0.0 }
+    }
+
+    /// \[backend\] Multiplies a world space vector by the inverse of the inertia tensor of the body in world space.
+    /// # Arguments
+    /// * `rotation` - The rotation of the GameObject in world space.
+    /// * `vector` - The vector.
+    pub(crate) fn inverse_inertia(&self, rotation: Quaternion<f32>, vector: Vector3<f32>) -> Vector3<f32> {
+        if !self.is_dynamic() {
+            return Vector3(0.0, 0.0, 0.0);
+        }
+        let invert = |moment: f32| if moment > 0.0 { 1.0 / moment } else { 0.0 };
+
+        // move into the space of the principal axes, scale and move back
+        let local = rotation.reciprocal() * Quaternion::new_vector_real(vector, 0.0) * rotation;
+        let scaled = Vector3(local.0 * invert(self.inertia.0), local.1 * invert(self.inertia.1), local.2 * invert(self.inertia.2));
+        let world = rotation * Quaternion::new_vector_real(scaled, 0.0) * rotation.reciprocal();
+        Vector3(world.0, world.1, world.2)
+    }
+
+    /// \[backend\] Clears the forces and torques at the end of each frame.
+    pub(crate) fn clear_forces(&mut self) {
+        self.force = Vector3(0.0, 0.0, 0.0);
+        self.torque = Vector3(0.0, 0.0, 0.0);
+    }
+}
@@ -0,0 +1,464 @@
+//! \[backend\] Moves the rigid bodies by one fixed step.
+//!
+//! The velocities are integrated first, then the contacts and joints are solved with sequential impulses
+//! so that bodies bounce and slide off each other and stay joined. The positions are integrated with the
+//! new velocities and finally pushed apart along the contact normals to undo any remaining overlap. Joints
+//! undo their own drift by steering the velocities back towards their anchors.
+//!
+use {
+    super::{
+        PhysicsWorld,
+        rigid_body::RigidBody,
+        joint::{Joint, JointKind, Motor},
+    },
+    crate::{
+        scene::{
+            Scene,
+            game_object::GameObject,
+        },
+        registration::id::ID,
+    },
+    feo_math::{
+        linear_algebra::vector3::Vector3,
+        rotation::quaternion::Quaternion,
+    },
+    std::{
+        collections::HashMap,
+        sync::{Arc, RwLock},
+    },
+};
+
+const ITERATIONS: usize = 8;
+const RESTING_SPEED: f32 = 0.5; // slower impacts do not bounce so resting bodies do not jitter
+const SLOP: f32 = 0.005; // overlap left alone so that resting contacts persist
+const CORRECTION: f32 = 0.4; // the fraction of the overlap undone each step
+const JOINT_CORRECTION: f32 = 0.2; // the fraction of the drift of a joint undone each step
+
+/// A rigid body along with its GameObject and where it is in world space.
+struct State {
+    id: ID,
+    game_object: Arc<RwLock<dyn GameObject>>,
+    body: RigidBody,
+    position: Vector3<f32>,
+    rotation: Quaternion<f32>,
+}
+
+impl State {
+    fn velocity_at(&self, offset: Vector3<f32>) -> Vector3<f32> {
+        self.body.velocity + Vector3::cross_product(self.body.angular_velocity, offset)
+    }
+
+    fn apply_impulse(&mut self, impulse: Vector3<f32>, offset: Vector3<f32>) {
+        self.body.velocity = self.body.velocity + impulse * self.body.inverse_mass();
+        self.body.angular_velocity = self.body.angular_velocity + self.body.inverse_inertia(self.rotation, Vector3::cross_product(offset, impulse));
+    }
+
+    /// Gets the inverse of the mass felt along a direction at an offset from the center of mass.
+    fn inverse_mass_along(&self, offset: Vector3<f32>, direction: Vector3<f32>) -> f32 {
+        let turn = self.body.inverse_inertia(self.rotation, Vector3::cross_product(offset, direction));
+        self.body.inverse_mass() + Vector3::dot_product(Vector3::cross_product(turn, offset), direction)
+    }
+}
+
+/// A contact between a rigid body and another collider. A collider without a rigid body can not move.
+struct Constraint {
+    a: Option<usize>,
+    b: Option<usize>,
+    offset_a: Vector3<f32>,
+    offset_b: Vector3<f32>,
+    normal: Vector3<f32>, // from a to b
+    tangents: [Vector3<f32>; 2],
+    depth: f32,
+    friction: f32,
+    target_speed: f32, // the speed the bodies should separate at
+    normal_mass: f32,
+    tangent_mass: [f32; 2],
+    normal_impulse: f32,
+    tangent_impulse: [f32; 2],
+}
+
+fn relative_velocity(states: &[State], constraint: &Constraint) -> Vector3<f32> {
+    let velocity = |index: Option<usize>, offset: Vector3<f32>| index.map_or(Vector3(0.0, 0.0, 0.0), |i| states[i].velocity_at(offset));
+    velocity(constraint.b, constraint.offset_b) - velocity(constraint.a, constraint.offset_a)
+}
+
+fn apply(states: &mut [State], constraint: &Constraint, impulse: Vector3<f32>) {
+    if let Some(a) = constraint.a {
+        states[a].apply_impulse(-impulse, constraint.offset_a);
+    }
+    if let Some(b) = constraint.b {
+        states[b].apply_impulse(impulse, constraint.offset_b);
+    }
+}
+
+/// A direction along which a joint holds two bodies. It either stops them moving apart along it, limits
+/// how far they move, drives them or pulls them like a spring.
+struct Row {
+    a: Option<usize>,
+    b: Option<usize>,
+    linear: Vector3<f32>, // the direction the anchors are held along. Zero for turning.
+    angular_a: Vector3<f32>, // how turning each body moves it along the row
+    angular_b: Vector3<f32>,
+    target_speed: f32, // the speed of b relative to a along the row
+    softness: f32, // how much a spring gives way. 0 for rigid rows.
+    mass: f32,
+    bounds: (f32, f32), // the lowest and highest total impulse
+    impulse: f32,
+}
+
+impl Row {
+    #[allow(clippy::too_many_arguments)]
+    fn new(states: &[State], a: Option<usize>, b: Option<usize>, linear: Vector3<f32>, angular_a: Vector3<f32>, angular_b: Vector3<f32>, target_speed: f32, softness: f32, bounds: (f32, f32)) -> Self {
+        let part = |index: Option<usize>, angular: Vector3<f32>| index.map_or(0.0, |i| {
+            let state = &states[i];
+            state.body.inverse_mass() * Vector3::dot_product(linear, linear)
+                + Vector3::dot_product(angular, state.body.inverse_inertia(state.rotation, angular))
+        });
+        let sum = part(a, angular_a) + part(b, angular_b) + softness;
+        Row {
+            a, b, linear, angular_a, angular_b, target_speed, softness,
+            mass: if sum > 0.0 { 1.0 / sum } else { 0.0 },
+            bounds,
+            impulse: 0.0,
+        }
+    }
+
+    /// A row holding the anchors together along a direction.
+    fn linear(states: &[State], ends: &Ends, direction: Vector3<f32>, target_speed: f32, softness: f32, bounds: (f32, f32)) -> Self {
+        Row::new(
+            states, ends.a, ends.b, direction,
+            Vector3::cross_product(ends.offset_a, direction), Vector3::cross_product(ends.offset_b, direction),
+            target_speed, softness, bounds,
+        )
+    }
+
+    /// A row stopping the bodies turning relative to each other around an axis.
+    fn angular(states: &[State], ends: &Ends, axis: Vector3<f32>, target_speed: f32, bounds: (f32, f32)) -> Self {
+        Row::new(states, ends.a, ends.b, Vector3(0.0, 0.0, 0.0), axis, axis, target_speed, 0.0, bounds)
+    }
+
+    fn speed(&self, states: &[State]) -> f32 {
+        let speed = |index: Option<usize>, angular: Vector3<f32>| index.map_or(0.0, |i| {
+            Vector3::dot_product(self.linear, states[i].body.velocity) + Vector3::dot_product(angular, states[i].body.angular_velocity)
+        });
+        speed(self.b, self.angular_b) - speed(self.a, self.angular_a)
+    }
+
+    fn solve(&mut self, states: &mut [State]) {
+        let total = (self.impulse + (self.target_speed - self.speed(states) - self.softness * self.impulse) * self.mass)
+            .max(self.bounds.0)
+            .min(self.bounds.1);
+        let impulse = total - self.impulse;
+        self.impulse = total;
+
+        let mut push = |index: Option<usize>, angular: Vector3<f32>, impulse: f32| if let Some(i) = index {
+            let state = &mut states[i];
+            state.body.velocity = state.body.velocity + self.linear * (impulse * state.body.inverse_mass());
+            state.body.angular_velocity = state.body.angular_velocity + state.body.inverse_inertia(state.rotation, angular * impulse);
+        };
+        push(self.a, self.angular_a, -impulse);
+        push(self.b, self.angular_b, impulse);
+    }
+}
+
+/// The two ends of a joint. A GameObject without a rigid body has no index.
+struct Ends {
+    a: Option<usize>,
+    b: Option<usize>,
+    rotation_a: Quaternion<f32>,
+    offset_a: Vector3<f32>, // from the position of each GameObject to its anchor in world space
+    offset_b: Vector3<f32>,
+    separation: Vector3<f32>, // from the anchor of a to the anchor of b
+    turn: Vector3<f32>, // the rotation of b relative to a since they were joined as an axis scaled by the angle in the space of a
+}
+
+fn rotate(rotation: Quaternion<f32>, vector: Vector3<f32>) -> Vector3<f32> {
+    let rotated = rotation * Quaternion::new_vector_real(vector, 0.0) * rotation.reciprocal();
+    Vector3(rotated.0, rotated.1, rotated.2)
+}
+
+const FREE: (f32, f32) = (f32::NEG_INFINITY, f32::INFINITY);
+
+/// Gets the rows holding the ends of a joint together.
+fn joint_rows(states: &[State], ends: &Ends, joint: &Joint, delta: f32) -> Vec<Row> {
+    let correct = |error: f32| -JOINT_CORRECTION * error / delta;
+    let axes = [Vector3(1.0, 0.0, 0.0), Vector3(0.0, 1.0, 0.0), Vector3(0.0, 0.0, 1.0)];
+    let point = || axes.iter().map(|&axis| {
+        Row::linear(states, ends, axis, correct(Vector3::dot_product(ends.separation, axis)), 0.0, FREE)
+    });
+    let turn = rotate(ends.rotation_a, ends.turn); // in world space
+    let locked = || axes.iter().map(move |&axis| Row::angular(states, ends, axis, correct(Vector3::dot_product(turn, axis)), FREE));
+    // the rows only push once the value would pass a limit within the step
+    let limit = |value: f32, (lower, upper): (f32, f32), row: &dyn Fn(f32, (f32, f32)) -> Row| {
+        let speed = |error: f32| if error < 0.0 { correct(error) } else { -error / delta };
+        [row(speed(value - lower), (0.0, f32::INFINITY)), row(-speed(upper - value), (f32::NEG_INFINITY, 0.0))]
+    };
+    let motor = |motor: &Motor, row: &dyn Fn(f32, (f32, f32)) -> Row| {
+        let most = motor.max_force.abs() * delta;
+        row(motor.speed, (-most, most))
+    };
+
+    match &joint.kind {
+        JointKind::Fixed => point().chain(locked()).collect(),
+        JointKind::BallSocket => point().collect(),
+        JointKind::Hinge { axis, limits, motor: drive } => {
+            let axis = axis.unit_vector();
+            let world_axis = rotate(ends.rotation_a, axis);
+            // only the turning around the axis is free
+            let swing = turn - world_axis * Vector3::dot_product(turn, world_axis);
+            let mut rows: Vec<Row> = point()
+                .chain(tangents(world_axis).iter().map(|&tangent| Row::angular(states, ends, tangent, correct(Vector3::dot_product(swing, tangent)), FREE)))
+                .collect();
+
+            let around = |target_speed: f32, bounds: (f32, f32)| Row::angular(states, ends, world_axis, target_speed, bounds);
+            if let Some(limits) = limits {
+                rows.extend(limit(Vector3::dot_product(ends.turn, axis), *limits, &around));
+            }
+            if let Some(drive) = drive {
+                rows.push(motor(drive, &around));
+            }
+            rows
+        },
+        JointKind::Slider { axis, limits, motor: drive } => {
+            let world_axis = rotate(ends.rotation_a, axis.unit_vector());
+            let mut rows: Vec<Row> = tangents(world_axis).iter()
+                .map(|&tangent| Row::linear(states, ends, tangent, correct(Vector3::dot_product(ends.separation, tangent)), 0.0, FREE))
+                .chain(locked())
+                .collect();
+
+            let along = |target_speed: f32, bounds: (f32, f32)| Row::linear(states, ends, world_axis, target_speed, 0.0, bounds);
+            if let Some(limits) = limits {
+                rows.extend(limit(Vector3::dot_product(ends.separation, world_axis), *limits, &along));
+            }
+            if let Some(drive) = drive {
+                rows.push(motor(drive, &along));
+            }
+            rows
+        },
+        JointKind::Spring { rest_length, stiffness, damping, limits } => {
+            let length = ends.separation.norm();
+            if length <= f32::EPSILON {
+                return Vec::new();
+            }
+            let direction = ends.separation * (1.0 / length);
+            let along = |target_speed: f32, softness: f32, bounds: (f32, f32)| Row::linear(states, ends, direction, target_speed, softness, bounds);
+            let mut rows = Vec::new();
+
+            // a soft constraint is a spring that stays steady however stiff it is
+            let (stiffness, damping) = (stiffness.max(0.0), damping.max(0.0));
+            let give = damping + delta * stiffness;
+            if give > 0.0 {
+                rows.push(along(-stiffness * (length - rest_length) / give, 1.0 / (delta * give), FREE));
+            }
+            if let Some(limits) = limits {
+                rows.extend(limit(length, *limits, &|target_speed, bounds| along(target_speed, 0.0, bounds)));
+            }
+            rows
+        },
+    }
+}
+
+/// Gets two directions perpendicular to the normal and each other.
+fn tangents(normal: Vector3<f32>) -> [Vector3<f32>; 2] {
+    let axis = if normal.0.abs() < 0.57 { Vector3(1.0, 0.0, 0.0) } else { Vector3(0.0, 1.0, 0.0) };
+    let first = Vector3::cross_product(normal, axis).unit_vector();
+    [first, Vector3::cross_product(normal, first)]
+}
+
+/// Moves every active rigid body by one step.
+/// # Arguments
+/// * `world` - The physics state of the scene.
+/// * `scene` - The scene.
+/// * `delta` - The length of the step in seconds.
+/// * `gravity` - The acceleration due to gravity.
+pub(super) fn step(world: &PhysicsWorld, scene: &Scene, delta: f32, gravity: Vector3<f32>) {
+    let bodies: Vec<(ID, RigidBody)> = {
+        let mut bodies = world.rigid_bodies.lock().unwrap();
+        bodies.retain(|id, _| id.is_alive());
+        bodies.iter().map(|(id, body)| (id.clone(), body.clone())).collect()
+    };
+
+    let mut states: Vec<State> = bodies.into_iter().filter_map(|(id, body)| {
+        let game_object = scene.find_by_id(&id)?;
+        let (position, rotation) = {
+            let read = game_object.read().unwrap();
+            if !read.is_active_in_hierarchy() {
+                return None;
+            }
+            (read.world_position(), read.world_rotation().unit_quaternion())
+        };
+        Some(State { id, game_object, body, position, rotation })
+    }).collect();
+    let key = |id: &ID| (id.get_value(), id.get_generation()); // every ID of a scene is from the same system
+    let indices: HashMap<(usize, usize), usize> = states.iter().enumerate().map(|(i, state)| (key(&state.id), i)).collect();
+
+    // forces, gravity and impulses
+    states.iter_mut().for_each(|state| {
+        let rotation = state.rotation;
+        let body = &mut state.body;
+        if body.is_dynamic() {
+            let inverse_mass = body.inverse_mass();
+            let gravity = if body.use_gravity { gravity } else { Vector3(0.0, 0.0, 0.0) };
+            body.velocity = body.velocity + (gravity + body.force * inverse_mass) * delta + body.impulse * inverse_mass;
+            body.angular_velocity = body.angular_velocity + body.inverse_inertia(rotation, body.torque * delta + body.angular_impulse);
+
+            body.velocity = body.velocity * (1.0 / (1.0 + delta * body.linear_damping.max(0.0)));
+            body.angular_velocity = body.angular_velocity * (1.0 / (1.0 + delta * body.angular_damping.max(0.0)));
+        }
+        body.impulse = Vector3(0.0, 0.0, 0.0);
+        body.angular_impulse = Vector3(0.0, 0.0, 0.0);
+    });
+
+    // contacts
+    let mut constraints: Vec<Constraint> = world.contacts(scene).into_iter().filter_map(|(a, b, contact)| {
+        let a = indices.get(&key(&a.read().unwrap().get_id())).copied();
+        let b = indices.get(&key(&b.read().unwrap().get_id())).copied();
+        let movable = |index: Option<usize>| index.is_some_and(|i| states[i].body.is_dynamic());
+        if !movable(a) && !movable(b) {
+            return None;
+        }
+
+        let offset = |index: Option<usize>| index.map_or(Vector3(0.0, 0.0, 0.0), |i| contact.point - states[i].position);
+        let inverse_mass = |index: Option<usize>, offset: Vector3<f32>, direction: Vector3<f32>| {
+            index.map_or(0.0, |i| states[i].inverse_mass_along(offset, direction))
+        };
+        let material = |index: Option<usize>| index.map(|i| (states[i].body.friction.max(0.0), states[i].body.restitution.max(0.0)));
+        let (friction, restitution) = match (material(a), material(b)) {
+            (Some((friction_a, restitution_a)), Some((friction_b, restitution_b))) => ((friction_a * friction_b).sqrt(), restitution_a.max(restitution_b)),
+            (Some(material), None) | (None, Some(material)) => material,
+            (None, None) => unreachable!(),
+        };
+
+        let (offset_a, offset_b) = (offset(a), offset(b));
+        let normal = contact.normal;
+        let tangents = tangents(normal);
+        let effective_mass = |direction: Vector3<f32>| {
+            let sum = inverse_mass(a, offset_a, direction) + inverse_mass(b, offset_b, direction);
+            if sum > 0.0 { 1.0 / sum } else { 0.0 }
+        };
+
+        let mut constraint = Constraint {
+            a, b, offset_a, offset_b, normal, tangents,
+            depth: contact.depth,
+            friction,
+            target_speed: 0.0,
+            normal_mass: effective_mass(normal),
+            tangent_mass: [effective_mass(tangents[0]), effective_mass(tangents[1])],
+            normal_impulse: 0.0,
+            tangent_impulse: [0.0, 0.0],
+        };
+        let approach = -Vector3::dot_product(relative_velocity(&states, &constraint), normal);
+        if approach > RESTING_SPEED {
+            constraint.target_speed = restitution * approach;
+        }
+        Some(constraint)
+    }).collect();
+
+    // joints
+    let joints: Vec<(ID, ID, Joint)> = {
+        let mut joints = world.joints.lock().unwrap();
+        joints.retain(|(a, b), _| a.is_alive() && b.is_alive());
+        joints.iter().map(|((a, b), joint)| (a.clone(), b.clone(), joint.clone())).collect()
+    };
+    let mut rows: Vec<Row> = joints.into_iter()
+        .filter_map(|(a, b, joint)| {
+            // a GameObject without a rigid body is held in place where it is
+            let end = |id: &ID| match indices.get(&key(id)) {
+                Some(&i) => Some((Some(i), states[i].position, states[i].rotation)),
+                None => {
+                    let game_object = scene.find_by_id(id)?;
+                    let read = game_object.read().unwrap();
+                    read.is_active_in_hierarchy().then(|| (None, read.world_position(), read.world_rotation().unit_quaternion()))
+                },
+            };
+            let ((index_a, position_a, rotation_a), (index_b, position_b, rotation_b)) = (end(&a)?, end(&b)?);
+            if ![index_a, index_b].iter().flatten().any(|&i| states[i].body.is_dynamic()) {
+                return None;
+            }
+
+            let (offset_a, offset_b) = (rotate(rotation_a, joint.anchor_a), rotate(rotation_b, joint.anchor_b));
+            let mut turn = rotation_a.reciprocal() * rotation_b * joint.reference.reciprocal();
+            if turn.3 < 0.0 {
+                turn = -turn; // the shorter way around
+            }
+            let axis = Vector3(turn.0, turn.1, turn.2);
+            let sine = axis.norm();
+            let ends = Ends {
+                a: index_a,
+                b: index_b,
+                rotation_a,
+                offset_a,
+                offset_b,
+                separation: (position_b + offset_b) - (position_a + offset_a),
+                turn: if sine > f32::EPSILON { axis * (2.0 * sine.atan2(turn.3) / sine) } else { axis * 2.0 },
+            };
+            Some(joint_rows(&states, &ends, &joint, delta))
+        })
+        .flatten()
+        .collect();
+
+    for _ in 0..ITERATIONS {
+        rows.iter_mut().for_each(|row| row.solve(&mut states));
+        for constraint in constraints.iter_mut() {
+            // the bodies must not move into each other
+            let speed = Vector3::dot_product(relative_velocity(&states, constraint), constraint.normal);
+            let total = (constraint.normal_impulse + (constraint.target_speed - speed) * constraint.normal_mass).max(0.0);
+            let impulse = total - constraint.normal_impulse;
+            constraint.normal_impulse = total;
+            apply(&mut states, constraint, constraint.normal * impulse);
+
+            // friction can not be stronger than the push between the bodies
+            let limit = constraint.friction * constraint.normal_impulse;
+            for i in 0..2 {
+                let tangent = constraint.tangents[i];
+                let speed = Vector3::dot_product(relative_velocity(&states, constraint), tangent);
+                let total = (constraint.tangent_impulse[i] - speed * constraint.tangent_mass[i]).max(-limit).min(limit);
+                let impulse = total - constraint.tangent_impulse[i];
+                constraint.tangent_impulse[i] = total;
+                apply(&mut states, constraint, tangent * impulse);
+            }
+        }
+    }
+
+    // positions
+    states.iter_mut().for_each(|state| {
+        let body = &state.body;
+        state.position = state.position + body.velocity * delta;
+        let spin = Quaternion::new_vector_real(body.angular_velocity, 0.0) * state.rotation * (0.5 * delta);
+        state.rotation = (state.rotation + spin).unit_quaternion();
+    });
+
+    // undo the overlap the velocities did not
+    constraints.iter().for_each(|constraint| {
+        let inverse_mass = |index: Option<usize>| index.map_or(0.0, |i| states[i].body.inverse_mass());
+        let (inverse_mass_a, inverse_mass_b) = (inverse_mass(constraint.a), inverse_mass(constraint.b));
+        let correction = (constraint.depth - SLOP).max(0.0) * CORRECTION / (inverse_mass_a + inverse_mass_b);
+        if let Some(a) = constraint.a {
+            states[a].position = states[a].position - constraint.normal * (correction * inverse_mass_a);
+        }
+        if let Some(b) = constraint.b {
+            states[b].position = states[b].position + constraint.normal * (correction * inverse_mass_b);
+        }
+    });
+
+    // write the results back
+    {
+        let mut bodies = world.rigid_bodies.lock().unwrap();
+        states.iter().for_each(|state| {
+            if let Some(body) = bodies.get_mut(&state.id) {
+                body.velocity = state.body.velocity;
+                body.angular_velocity = state.body.angular_velocity;
+                body.impulse = Vector3(0.0, 0.0, 0.0);
+                body.angular_impulse = Vector3(0.0, 0.0, 0.0);
+            }
+        });
+    }
+    states.into_iter().for_each(|state| {
+        let moving = state.body.is_dynamic() || state.body.velocity != Vector3(0.0, 0.0, 0.0) || state.body.angular_velocity != Vector3(0.0, 0.0, 0.0);
+        if moving {
+            let mut game_object = state.game_object.write().unwrap();
+            game_object.set_world_position(state.position);
+            game_object.set_world_rotation(state.rotation);
+        }
+    });
+}
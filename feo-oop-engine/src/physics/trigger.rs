@@ -0,0 +1,210 @@
+//! Trigger volumes that detect GameObjects without blocking them.
+//!
+//! A trigger is attached to a GameObject with `Scene::set_trigger`. It is kept apart from the colliders
+//! so a GameObject can have both. Every frame after the physics step the triggers are tested against the
+//! colliders of the other GameObjects. Rigid bodies pass through triggers and triggers do not detect each other.
+//!
+//! Both the GameObject with the trigger and the GameObject within it receive the events through their own
+//! event handlers. `TriggerEnter` is sent on the first frame they overlap, `TriggerStay` on every following
+//! frame and `TriggerExit` on the first frame they no longer overlap. A GameObject that is deleted or
+//! disabled while within a trigger exits it.
+//!
+//! ## Example
+//! ```no_run
+//! # use feo_oop_engine::physics::trigger::{Trigger, TriggerShape};
+//! # use feo_math::linear_algebra::vector3::Vector3;
+//! # fn f(checkpoint: std::sync::Arc<std::sync::RwLock<dyn feo_oop_engine::scene::game_object::GameObject>>, engine_globals: feo_oop_engine::scripting::globals::EngineGlobals) {
+//! let id = checkpoint.read().unwrap().get_id();
+//! engine_globals.scene.read().unwrap().set_trigger(id, Trigger::new(TriggerShape::Box { half_extents: Vector3(2.0, 2.0, 0.5) }));
+//! # }
+//! ```
+//! Then within the event handler of the checkpoint
+//! ```ignore
+//! if let Event::UserEvent(UserEvent::TriggerEnter(_this, other)) = event {
+//!     println!("{} reached the checkpoint", other.read().unwrap().get_name());
+//! }
+//! ```
+//!
+use {
+    super::{
+        PhysicsWorld,
+        gjk,
+        collider::{Collider, ColliderShape, WorldShape},
+    },
+    crate::{
+        scene::{
+            Scene,
+            game_object::GameObject,
+        },
+        registration::id::ID,
+        scripting::executor::Spawner,
+        event::UserEvent,
+    },
+    feo_math::{
+        linear_algebra::vector3::Vector3,
+        utils::space::Space,
+    },
+    std::{
+        collections::HashMap,
+        sync::{Arc, RwLock},
+    },
+    winit::event::Event,
+};
+
+/// The shape of a trigger.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriggerShape {
+    /// A box that rotates with its GameObject.
+    Box { half_extents: Vector3<f32> },
+    /// A sphere. Scaled by the largest scale factor of its GameObject.
+    Sphere { radius: f32 },
+}
+
+/// A trigger volume attached to a GameObject. See `Scene::set_trigger`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trigger {
+    pub shape: TriggerShape,
+    pub offset: Vector3<f32>, // the center of the shape in the space of the GameObject
+}
+
+impl Trigger {
+    /// Creates a trigger centered on its GameObject.
+    pub fn new(shape: TriggerShape) -> Self {
+        Trigger {
+            shape,
+            offset: Vector3(0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Moves the center of the shape within the space of the GameObject.
+    pub fn with_offset(mut self, offset: Vector3<f32>) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    fn to_world(&self, space: &Space) -> WorldShape {
+        let shape = match self.shape {
+            TriggerShape::Box { half_extents } => ColliderShape::Obb { half_extents },
+            TriggerShape::Sphere { radius } => ColliderShape::Sphere { radius },
+        };
+        Collider::new(shape).with_offset(self.offset).to_world(space)
+    }
+}
+
+/// \[backend\] A GameObject within a trigger.
+#[derive(Debug, Clone)]
+pub(crate) struct Overlap {
+    trigger: Arc<RwLock<dyn GameObject>>,
+    other: Arc<RwLock<dyn GameObject>>,
+}
+
+/// Which of the trigger events to send.
+enum Phase {
+    Enter,
+    Stay,
+    Exit,
+}
+
+impl PhysicsWorld {
+    /// Finds every GameObject within each trigger and how that changed since the last frame.
+    #[allow(clippy::mutable_key_type)] // IDs hash by their index, generation and the address of their system
+    fn update_overlaps(&self, scene: &Scene) -> Vec<(Overlap, Phase)> {
+        let triggers: Vec<(ID, Trigger)> = {
+            let mut triggers = self.triggers.lock().unwrap();
+            triggers.retain(|id, _| id.is_alive());
+            triggers.iter().map(|(id, trigger)| (id.clone(), trigger.clone())).collect()
+        };
+        let triggers: Vec<(Arc<RwLock<dyn GameObject>>, WorldShape)> = triggers.into_iter().filter_map(|(id, trigger)| {
+            let game_object = scene.find_by_id(&id)?;
+            let shape = {
+                let read = game_object.read().unwrap();
+                if !read.is_active_in_hierarchy() {
+                    return None;
+                }
+                trigger.to_world(&read.get_subspace())
+            };
+            Some((game_object, shape))
+        }).collect();
+        let bodies = self.bodies(scene);
+
+        let mut current: HashMap<(ID, ID), Overlap> = HashMap::new();
+        triggers.iter().for_each(|(trigger, shape)| {
+            let trigger_id = trigger.read().unwrap().get_id();
+            let bounds = shape.bounds();
+            bodies.iter().for_each(|body| {
+                if Arc::ptr_eq(trigger, &body.game_object) || !bounds.intersects(&body.shape.bounds()) {
+                    return;
+                }
+                if gjk::collide(shape, &body.shape).is_some() {
+                    let other_id = body.game_object.read().unwrap().get_id();
+                    current.insert((trigger_id.clone(), other_id), Overlap { trigger: trigger.clone(), other: body.game_object.clone() });
+                }
+            });
+        });
+
+        let mut overlaps = self.overlaps.lock().unwrap();
+        let previous = std::mem::replace(&mut *overlaps, current);
+        let mut changes: Vec<(Overlap, Phase)> = overlaps.iter()
+            .map(|(key, overlap)| {
+                let phase = if previous.contains_key(key) { Phase::Stay } else { Phase::Enter };
+                (overlap.clone(), phase)
+            })
+            .collect();
+        changes.extend(previous.into_iter()
+            .filter(|(key, _)| !overlaps.contains_key(key))
+            .map(|(_, overlap)| (overlap, Phase::Exit)));
+        changes
+    }
+}
+
+impl Scene {
+    /// Attaches a trigger to a GameObject. Replaces any trigger it already has.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `trigger` - The trigger.
+    pub fn set_trigger(&self, id: ID, trigger: Trigger) {
+        self.physics.triggers.lock().unwrap().insert(id, trigger);
+    }
+
+    /// Removes the trigger of a GameObject. The GameObjects within it receive a `TriggerExit` event on the next frame.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn remove_trigger(&self, id: &ID) -> Option<Trigger> {
+        self.physics.triggers.lock().unwrap().remove(id)
+    }
+
+    /// Gets the trigger of a GameObject.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn get_trigger(&self, id: &ID) -> Option<Trigger> {
+        self.physics.triggers.lock().unwrap().get(id).cloned()
+    }
+
+    /// Gets the GameObjects that were within a trigger on the last frame.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject with the trigger.
+    pub fn get_overlapping(&self, id: &ID) -> Vec<Arc<RwLock<dyn GameObject>>> {
+        self.physics.overlaps.lock().unwrap().iter()
+            .filter(|((trigger_id, _), _)| trigger_id == id)
+            .map(|(_, overlap)| overlap.other.clone())
+            .collect()
+    }
+
+    /// \[backend\] Sends the trigger events to the GameObjects with triggers and to the GameObjects within them. Run once per frame.
+    pub fn detect_triggers(&self, spawner: Spawner) {
+        self.physics.update_overlaps(self).into_iter().for_each(|(Overlap { trigger, other }, phase)| {
+            let event = |this: &Arc<RwLock<dyn GameObject>>, other: &Arc<RwLock<dyn GameObject>>| Event::UserEvent(match phase {
+                Phase::Enter => UserEvent::TriggerEnter(this.clone(), other.clone()),
+                Phase::Stay => UserEvent::TriggerStay(this.clone(), other.clone()),
+                Phase::Exit => UserEvent::TriggerExit(this.clone(), other.clone()),
+            });
+            [(&trigger, &other), (&other, &trigger)].into_iter().for_each(|(this, other)| {
+                // deleted GameObjects no longer receive events
+                if this.read().unwrap().get_id().is_alive() {
+                    let event = event(this, other);
+                    this.write().unwrap().spawn_own_script_handler(this.clone(), spawner.clone(), event);
+                }
+            });
+        });
+    }
+}
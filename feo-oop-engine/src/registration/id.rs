@@ -1,25 +1,29 @@
 //! Identification systems
-//! 
-//! TODO
-//! 
-use std::sync::{Arc, Mutex};
+//!
+//! IDs are generational. An ID is made up of an index and the generation of that index.
+//! When an ID is freed the generation of its index is incremented, so any copy of the
+//! freed ID that is still around can be detected as stale using `ID::is_alive`.
+//!
+use std::{collections::VecDeque, sync::{Arc, Mutex}};
 
+/// Identifies a GameObject.
+///
+/// Cloning an ID only creates another handle to the same GameObject. Two GameObjects can
+/// never be given the same ID because an index is only reused once its generation changes.
 #[derive(Debug, PartialEq, Eq, Hash)]
-#[derive(Clone)] // For now you can clone an ID but not create two objects with the same IDs 
-// Bug: TOFIX returning a cloned ID may lead to two objects with the same ID being created
-pub struct ID(usize, IDSystem);
+#[derive(Clone)]
+pub struct ID(usize /* index */, usize /* generation */, IDSystem);
 
 impl PartialOrd for ID {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        assert_eq!(self.1, other.1);
-        self.0.partial_cmp(&other.0)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for ID {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        assert_eq!(self.1, other.1);
-        self.0.cmp(&other.0)
+        assert_eq!(self.2, other.2);
+        self.0.cmp(&other.0).then(self.1.cmp(&other.1))
     }
 }
 
@@ -31,7 +35,11 @@ impl std::fmt::Display for ID {
 
 impl ID{
     pub fn get_value(&self) -> usize{ self.0 }
-    pub fn get_system(&self) -> IDSystem { self.1.clone() }
+    pub fn get_generation(&self) -> usize{ self.1 }
+    pub fn get_system(&self) -> IDSystem { self.2.clone() }
+
+    /// Returns false once the ID has been freed. i.e. The GameObject it identified was deleted.
+    pub fn is_alive(&self) -> bool { self.2.is_alive(self) }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -55,22 +63,81 @@ impl std::hash::Hash for IDSystem {
 
 #[derive(Debug, Default)]
 struct IDSystemInner {
-    free: Vec<usize>,
-    current: usize,
+    free: VecDeque<usize>,
+    generations: Vec<usize>, // the current generation of each index, index n is stored at n - 1
 }
 
 impl IDSystem {
-
+    /// Takes a new unique ID.
+    ///
+    /// Freed indices are reused oldest first.
     pub fn take(&self) -> ID {
-        let inner = self.inner.clone();
-        let inner = &mut inner.lock().unwrap();
-        match inner.free.pop() {
-            Some(id) => ID(id, self.clone()),
-            None => ID({inner.current += 1; inner.current}, self.clone())
+        let mut inner = self.inner.lock().unwrap();
+        match inner.free.pop_front() {
+            Some(index) => ID(index, inner.generations[index - 1], self.clone()),
+            None => {
+                inner.generations.push(0);
+                ID(inner.generations.len(), 0, self.clone())
+            }
         }
     }
 
-    pub fn free(&mut self, id: ID) {
-        self.inner.clone().lock().unwrap().free.push(id.0)
+    /// Frees an ID so that its index can be reused.
+    ///
+    /// Every copy of the ID becomes stale.
+    pub fn free(&self, id: &ID) -> Result<(), &'static str> {
+        if id.2 != *self {
+            return Err("The ID belongs to another IDSystem.");
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        let generation = &mut inner.generations[id.0 - 1];
+        if *generation != id.1 {
+            return Err("The ID has already been freed.");
+        }
+
+        *generation += 1;
+        inner.free.push_back(id.0);
+        Ok(())
+    }
+
+    /// Checks if an ID has not been freed.
+    pub fn is_alive(&self, id: &ID) -> bool {
+        id.2 == *self && self.inner.lock().unwrap().generations[id.0 - 1] == id.1
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_after_free_and_take() {
+        let system = IDSystem::default();
+        let id = system.take();
+        let copy = id.clone();
+        assert!(id.is_alive());
+
+        system.free(&id).unwrap();
+        let reused = system.take();
+        assert_eq!(reused.get_value(), id.get_value());
+        assert_ne!(reused.get_generation(), id.get_generation());
+
+        assert!(!id.is_alive());
+        assert!(!copy.is_alive());
+        assert!(reused.is_alive());
+        assert_ne!(reused, id);
+        assert!(system.free(&copy).is_err());
+    }
+
+    #[test]
+    fn foreign_ids_are_rejected() {
+        let system = IDSystem::default();
+        let other = IDSystem::default();
+        let id = other.take();
+        system.take();
+        assert!(!system.is_alive(&id));
+        assert!(system.free(&id).is_err());
+        assert!(id.is_alive());
+    }
+}
@@ -8,9 +8,9 @@ use {
             game_object::GameObject, 
             Scene,
         },
-        registration::named::Named
+        registration::{named::Named, id::ID}
     },
-    std::sync::{Arc, RwLock}
+    std::sync::{Arc, RwLock, Weak}
 };
 
 #[derive(Clone, Debug)]
@@ -19,6 +19,43 @@ pub enum ParentWrapper{ // make into a trait object and add as_parent
     Scene(Arc<RwLock<Scene>>)
 }
 
+impl ParentWrapper {
+    /// Gets the ID of the parent. Returns None if the parent is the scene.
+    pub fn get_id(&self) -> Option<ID> {
+        match self {
+            ParentWrapper::GameObject(game_object) => Some(game_object.read().unwrap().get_id()),
+            ParentWrapper::Scene(_) => None,
+        }
+    }
+
+    /// Creates a weak reference to the parent.
+    pub fn downgrade(&self) -> WeakParentWrapper {
+        match self {
+            ParentWrapper::GameObject(game_object) => WeakParentWrapper::GameObject(Arc::downgrade(game_object)),
+            ParentWrapper::Scene(scene) => WeakParentWrapper::Scene(Arc::downgrade(scene)),
+        }
+    }
+}
+
+/// A weak reference to a parent.
+/// 
+/// Children hold their parent weakly so that a parent and its children do not form a reference cycle.
+#[derive(Clone, Debug)]
+pub enum WeakParentWrapper{
+    GameObject(Weak<RwLock<dyn GameObject>>),
+    Scene(Weak<RwLock<Scene>>)
+}
+
+impl WeakParentWrapper {
+    /// Gets the parent if it still exists.
+    pub fn upgrade(&self) -> Option<ParentWrapper> {
+        match self {
+            WeakParentWrapper::GameObject(game_object) => game_object.upgrade().map(ParentWrapper::GameObject),
+            WeakParentWrapper::Scene(scene) => scene.upgrade().map(ParentWrapper::Scene),
+        }
+    }
+}
+
 pub trait Parent: Named {
     fn get_children(&self) -> Vec<Arc<RwLock<dyn GameObject /* dyn Child */>>>; // use dyn Child here and make gameobject castable to child using a new as_child() do same for parent except with as_parent and dyn Parent and not enum
     fn add_child(&mut self, child: Arc<RwLock<dyn GameObject>>);
@@ -47,7 +84,8 @@ pub trait Parent: Named {
 }
 
 pub trait Child: Named {
-    fn get_parent(&self) -> ParentWrapper; // await rmb
+    /// Gets the parent. Returns None if the parent no longer exists.
+    fn get_parent(&self) -> Option<ParentWrapper>; // await rmb
 
     /// # Safety
     /// Use `feo-oop-engine::scripting::swap::Swap` enum instead.
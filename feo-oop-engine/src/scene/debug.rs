@@ -0,0 +1,119 @@
+//! Views of the scene hierarchy for debugging.
+//!
+//! `Scene::dump_tree` prints one line per GameObject and `Scene::to_dot` exports the hierarchy
+//! for Graphviz. e.g. `dot -Tsvg scene.dot -o scene.svg`
+//!
+//! Note that like the other lookups these take a read lock on every GameObject in the scene.
+//!
+use {
+    super::{
+        Scene,
+        game_object::GameObject,
+    },
+    crate::registration::relation::{Parent, ParentWrapper},
+    feo_math::utils::space::Space,
+    std::{
+        fmt::Write,
+        sync::{Arc, RwLock},
+    },
+};
+
+impl Scene {
+    /// Prints the hierarchy of the scene. See `tree_string`.
+    pub fn dump_tree(&self) {
+        print!("{}", self.tree_string());
+    }
+
+    /// Writes the hierarchy of the scene with one line per GameObject showing its ID, name, type,
+    /// visibility, whether it is inactive, local transform, number of triangle meshes and whether it has a script.
+    /// # Examples
+    /// ```no_run
+    /// # let scene = feo_oop_engine::scene::Scene::new(None);
+    /// scene.read().unwrap().dump_tree();
+    /// // Scene
+    /// // └── 1 "player" Obj visible meshes: 1 script position: (0, 0, 0) rotation: (0, 0, 0, 1) scale: (1, 1, 1)
+    /// //     └── 2 "gun" Group hidden meshes: 0 position: (0.5, 0, -1) rotation: (0, 0, 0, 1) scale: (1, 1, 1)
+    /// ```
+    pub fn tree_string(&self) -> String {
+        let mut tree = String::from("Scene\n");
+        let children = self.get_children();
+        children.iter().enumerate().for_each(|(i, child)| {
+            write_tree(&mut tree, child, "", i + 1 == children.len());
+        });
+        tree
+    }
+
+    /// Exports the hierarchy of the scene in the Graphviz dot format.
+    ///
+    /// Hidden GameObjects are dashed, inactive GameObjects are grey and GameObjects with scripts are bold.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph scene {\n    node [shape=box];\n    scene [label=\"Scene\", shape=ellipse];\n");
+        self.descendants().for_each(|game_object| {
+            let game_object = game_object.read().unwrap();
+            let node = node_name(&*game_object);
+
+            let mut style = Vec::new();
+            if !game_object.get_visible() { style.push("dashed"); }
+            if game_object.has_script() { style.push("bold"); }
+            if !game_object.get_active() { style.push("filled"); }
+
+            let _ = writeln!(dot, "    {} [label=\"{} {}\\n{}\"{}{}];",
+                node,
+                game_object.get_id(),
+                escape(game_object.get_name()),
+                game_object.type_name(),
+                if style.is_empty() { String::new() } else { format!(", style=\"{}\"", style.join(",")) },
+                if game_object.get_active() { "" } else { ", fillcolor=lightgrey" }
+            );
+
+            let parent = match game_object.get_parent() {
+                Some(ParentWrapper::GameObject(parent)) => node_name(&*parent.read().unwrap()),
+                _ => String::from("scene"),
+            };
+            let _ = writeln!(dot, "    {} -> {};", parent, node);
+        });
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn write_tree(tree: &mut String, game_object: &Arc<RwLock<dyn GameObject>>, prefix: &str, last: bool) {
+    let game_object = game_object.read().unwrap();
+
+    let _ = writeln!(tree, "{}{}{} \"{}\" {} {}{} meshes: {}{} {}",
+        prefix,
+        if last { "└── " } else { "├── " },
+        game_object.get_id(),
+        game_object.get_name(),
+        game_object.type_name(),
+        if game_object.get_visible() { "visible" } else { "hidden" },
+        if game_object.get_active() { "" } else { " inactive" },
+        game_object.get_triangle_mesh().len(),
+        if game_object.has_script() { " script" } else { "" },
+        format_space(&game_object.get_transform().get_local())
+    );
+
+    let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+    let children = game_object.get_children();
+    children.iter().enumerate().for_each(|(i, child)| {
+        write_tree(tree, child, &prefix, i + 1 == children.len());
+    });
+}
+
+fn format_space(space: &Space) -> String {
+    let scale = &space.scale_factor.m;
+    format!("position: ({}, {}, {}) rotation: ({}, {}, {}, {}) scale: ({}, {}, {})",
+        space.center.0, space.center.1, space.center.2,
+        space.rotation.0, space.rotation.1, space.rotation.2, space.rotation.3,
+        scale[0][0], scale[1][1], scale[2][2]
+    )
+}
+
+fn node_name(game_object: &dyn GameObject) -> String {
+    let id = game_object.get_id();
+    format!("n{}_{}", id.get_value(), id.get_generation())
+}
+
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -14,7 +14,7 @@ use {
         registration::{
             relation::{
                 Child, Parent,
-                ParentWrapper
+                ParentWrapper, WeakParentWrapper
             },
             named::Named,
             id::ID
@@ -30,6 +30,7 @@ use {
         },
         components::{
             triangle_mesh::TriangleMesh,
+            transform::Transform,
         },
         graphics::{
             Drawable,
@@ -37,6 +38,7 @@ use {
             lighting_pass_manager::LightingPassManager,
         },
         event::UserEvent,
+        scene::serialization::GameObjectKind,
         shaders::vs_draw,
     },
     feo_math::{
@@ -63,7 +65,7 @@ use {
 pub struct FpvCamera{
     id: ID,
     name: String,
-    parent: ParentWrapper,
+    parent: WeakParentWrapper,
 
     main: bool,
 
@@ -73,8 +75,10 @@ pub struct FpvCamera{
     near_plane: f32,
     far_plane: f32,
     aspect_ratio: f32,
+    fixed_aspect_ratio: bool, // letterboxes rather than following the size of the image rendered to
 
-    pub subspace: Space,
+    pub subspace: Transform,
+    active: bool,
 
     script: Option<Box<Script<Self>>>,
 
@@ -93,7 +97,9 @@ impl std::fmt::Debug for FpvCamera {
             .field("near_plane", &self.near_plane)
             .field("far_plane", &self.far_plane)
             .field("aspect_ratio", &self.aspect_ratio)
+            .field("fixed_aspect_ratio", &self.fixed_aspect_ratio)
             .field("subspace", &self.subspace)
+            .field("active", &self.active)
             .field("script", &self.script)
             .field("children", &self.children).finish()
     }
@@ -106,18 +112,17 @@ impl Clone for FpvCamera {
             id,
             name: self.name.clone(),
             parent: self.parent.clone(),
-            subspace: self.subspace,
+            subspace: self.subspace.clone(),
+            active: self.active,
             script: self.script.clone(),
-            children: self.children.clone().into_iter().map(|_child| {
-                // Dangerous
-                todo!();
-            }).collect::<Vec<Arc<RwLock<dyn GameObject>>>>(),
+            children: Vec::new(), // see game_object::instantiate for copying children
             main: self.main,
             offset: self.offset,
             fov: self.fov,
             near_plane: self.near_plane,
             far_plane: self.far_plane,
             aspect_ratio: self.aspect_ratio,
+            fixed_aspect_ratio: self.fixed_aspect_ratio,
         }
     }
 }
@@ -153,7 +158,7 @@ impl FpvCamera {
             engine_globals: EngineGlobals//&VulkanoEngine
             ) -> Result<Arc<RwLock<Self>>, &'static str> { // TODO: pass surface
         let id = engine_globals.id_system.take();
-        let subspace = Space::new(position, rotation, scale_factor);
+        let subspace = Transform::new(Space::new(position, rotation, scale_factor));
         
         Ok(Arc::new(RwLock::new( FpvCamera {
             name: match name {
@@ -168,7 +173,7 @@ impl FpvCamera {
                 None => {
                     ParentWrapper::Scene(engine_globals.scene)
                 }
-            },
+            }.downgrade(),
 
             main,
             offset,
@@ -176,13 +181,28 @@ impl FpvCamera {
             near_plane,
             far_plane,
             aspect_ratio,
+            fixed_aspect_ratio: false,
             subspace,
+            active: true,
 
             script,
 
             children: Vec::new()
         })))
     }
+
+    /// \[backend\] Describes the FpvCamera for saving. See `scene::serialization`.
+    pub(crate) fn describe(&self) -> GameObjectKind {
+        GameObjectKind::FpvCamera {
+            main: self.main,
+            offset: self.offset.map(|offset| [offset.0, offset.1, offset.2]),
+            fov: self.fov,
+            near_plane: self.near_plane,
+            far_plane: self.far_plane,
+            aspect_ratio: self.aspect_ratio,
+            fixed_aspect_ratio: self.fixed_aspect_ratio,
+        }
+    }
 }
 
 impl Camera for FpvCamera {
@@ -198,6 +218,26 @@ impl Camera for FpvCamera {
     fn is_main(&self) -> bool {
         self.main
     }
+
+    fn get_aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    fn set_aspect_ratio(&mut self, aspect_ratio: f32) -> Result<(), &'static str> {
+        if aspect_ratio <= 0.0 || !aspect_ratio.is_finite() {
+            return Err("The aspect ratio of a camera must be positive.");
+        }
+        self.aspect_ratio = aspect_ratio;
+        Ok(())
+    }
+
+    fn is_aspect_ratio_fixed(&self) -> bool {
+        self.fixed_aspect_ratio
+    }
+
+    fn set_aspect_ratio_fixed(&mut self, fixed: bool) {
+        self.fixed_aspect_ratio = fixed;
+    }
     
     fn get_z_step (&self, z_buffer_size: usize) -> f32 {
         ((self.far_plane / self.near_plane) * 0.5).powi(z_buffer_size as i32)
@@ -5,6 +5,8 @@
 use std::fmt;
 
 pub mod fpv_camera;
+pub mod orbit_camera;
+pub mod orthographic_camera;
 
 use {
     super::GameObject,
@@ -23,6 +25,33 @@ pub trait Camera: CameraClone + GameObject {
     fn cast_gameobject_arc_rwlock(&self, this: Arc<RwLock<dyn Camera>>) -> Arc<RwLock<dyn GameObject>>; 
 
     fn is_main(&self) -> bool;
+
+    /// Gets the width of the view divided by its height. By default it is read from the projection.
+    fn get_aspect_ratio(&self) -> f32 {
+        let projection = self.build_projection();
+        (projection.m[1][1] / projection.m[0][0]).abs()
+    }
+    /// Sets the width of the view divided by its height. Fails if it is not positive.
+    /// By default it does nothing as the aspect ratio is fixed.
+    fn set_aspect_ratio(&mut self, _aspect_ratio: f32) -> Result<(), &'static str> {
+        Ok(())
+    }
+    /// Checks if the aspect ratio is kept when the image the camera renders to is resized. True by default.
+    fn is_aspect_ratio_fixed(&self) -> bool {
+        true
+    }
+    /// Keeps the aspect ratio when the image the camera renders to is resized. The view is then letterboxed
+    /// within the image rather than stretched. By default it does nothing as the aspect ratio is fixed.
+    fn set_aspect_ratio_fixed(&mut self, _fixed: bool) {}
+    /// Fits the view to the size of the image the camera renders to unless the aspect ratio is fixed.
+    /// This is done on every frame for the main camera and for the cameras rendering into textures.
+    /// # Arguments
+    /// * `dimensions` - The width and height of the image in pixels.
+    fn resize(&mut self, dimensions: [u32; 2]) {
+        if !self.is_aspect_ratio_fixed() && dimensions[0] > 0 && dimensions[1] > 0 {
+            self.set_aspect_ratio(dimensions[0] as f32 / dimensions[1] as f32).unwrap();
+        }
+    }
     
     fn get_z_step(&self, z_buffer_size: usize) -> f32;
 
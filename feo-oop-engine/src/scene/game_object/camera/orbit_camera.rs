@@ -0,0 +1,671 @@
+//! Orbit Camera GameObject that circles around a point of interest
+//!
+//! The camera looks at its focus from a distance. The focus is a target point or the position of a target
+//! GameObject moved by the pan. Yaw turns the camera around the world y axis and pitch raises it above the
+//! horizon. The camera eases towards changes over time according to its damping rather than jumping.
+//!
+//! `OrbitCamera::orbit_script` creates a script that drives the camera with the mouse. Dragging with the left
+//! button orbits, dragging with the middle button pans and the wheel zooms. A script of your own can do the
+//! same by calling `handle_event` from its event handler and `update` from its frame function.
+//!
+//! ## Example
+//! ```no_run
+//! # use feo_oop_engine::scene::game_object::camera::orbit_camera::{OrbitCamera, OrbitTarget};
+//! # use feo_math::linear_algebra::vector3::Vector3;
+//! # fn f(engine_globals: feo_oop_engine::scripting::globals::EngineGlobals) {
+//! // a model viewer looking at the origin from 5 units away
+//! let camera = OrbitCamera::new(
+//!     Some("viewer camera"),
+//!     true,
+//!     None,
+//!     OrbitTarget::Point(Vector3(0.0, 0.0, 0.0)),
+//!     0.0,
+//!     0.3,
+//!     5.0,
+//!     std::f32::consts::FRAC_PI_3,
+//!     0.1,
+//!     100.0,
+//!     16.0 / 9.0,
+//!     Some(OrbitCamera::orbit_script()),
+//!     engine_globals.clone()
+//! ).unwrap();
+//! engine_globals.scene.write().unwrap().set_main_camera(camera);
+//! # }
+//! ```
+//!
+use {
+    super::{
+        Camera,
+        super::{
+            GameObject,
+            light::Light,
+        }
+    },
+    crate::{
+        registration::{
+            relation::{
+                Child, Parent,
+                ParentWrapper, WeakParentWrapper
+            },
+            named::Named,
+            id::ID
+        },
+        scripting::{
+            Script,
+            executor::Spawner,
+            Scriptable,
+            swap::Swap,
+            globals::{
+                EngineGlobals,
+                Global
+            }
+        },
+        components::{
+            triangle_mesh::TriangleMesh,
+            transform::Transform,
+        },
+        graphics::{
+            Drawable,
+            draw_pass_manager::DrawPassManager,
+            lighting_pass_manager::LightingPassManager,
+        },
+        event::UserEvent,
+        scene::serialization::GameObjectKind,
+        shaders::vs_draw,
+    },
+    feo_math::{
+        linear_algebra::{
+            vector3::Vector3,
+            matrix4::Matrix4
+        },
+        utils::space::Space,
+        rotation::quaternion::Quaternion
+    },
+    futures::future::BoxFuture,
+    std::{
+        any::Any,
+        sync::{
+            Arc,
+            RwLock,
+            Weak
+        },
+        time::Instant,
+        mem
+    },
+    winit::event::{
+        Event,
+        WindowEvent,
+        ElementState,
+        MouseButton,
+        MouseScrollDelta
+    },
+};
+
+/// What an OrbitCamera circles around.
+#[derive(Debug, Clone)]
+pub enum OrbitTarget {
+    /// A point in world space.
+    Point(Vector3<f32>),
+    /// The world position of a GameObject. The camera stays where it was if the GameObject is dropped.
+    GameObject(Weak<RwLock<dyn GameObject>>),
+}
+
+impl OrbitTarget {
+    /// Follows a GameObject.
+    pub fn game_object(game_object: &Arc<RwLock<dyn GameObject>>) -> Self {
+        OrbitTarget::GameObject(Arc::downgrade(game_object))
+    }
+}
+
+/// \[backend\] Where the camera is around its target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Orbit {
+    yaw: f32,
+    pitch: f32,
+    distance: f32,
+    pan: Vector3<f32>, // the focus relative to the target in world space
+}
+
+impl Orbit {
+    fn rotation(&self) -> Quaternion<f32> {
+        Quaternion::new_axis_angle(Vector3(0.0, 1.0, 0.0), self.yaw) * Quaternion::new_axis_angle(Vector3(1.0, 0.0, 0.0), -self.pitch)
+    }
+
+    /// Moves part of the way to the goal.
+    fn approach(&self, goal: &Orbit, amount: f32) -> Orbit {
+        Orbit {
+            yaw: self.yaw + (goal.yaw - self.yaw) * amount,
+            pitch: self.pitch + (goal.pitch - self.pitch) * amount,
+            distance: self.distance + (goal.distance - self.distance) * amount,
+            pan: self.pan + (goal.pan - self.pan) * amount,
+        }
+    }
+}
+
+fn rotate(rotation: Quaternion<f32>, vector: Vector3<f32>) -> Vector3<f32> {
+    let rotated = rotation * Quaternion::new_vector_real(vector, 0.0) * rotation.reciprocal();
+    Vector3(rotated.0, rotated.1, rotated.2)
+}
+
+#[derive(Scriptable, GameObject, Parent, Child, Named, Drawable)]
+#[camera]
+pub struct OrbitCamera{
+    id: ID,
+    name: String,
+    parent: WeakParentWrapper,
+
+    main: bool,
+
+    fov: f32, // vertical in radians
+    near_plane: f32,
+    far_plane: f32,
+    aspect_ratio: f32,
+    fixed_aspect_ratio: bool, // letterboxes rather than following the size of the image rendered to
+
+    target: OrbitTarget,
+    last_target: Vector3<f32>, // where the target was on the last update
+    goal: Orbit,
+    current: Orbit,
+
+    pub damping: f32, // the part of the way to the goal left after each 60th of a second. 0 moves straight there
+    pub rotate_speed: f32, // radians per pixel dragged
+    pub pan_speed: f32, // the part of the distance moved per pixel dragged
+    pub zoom_speed: f32, // the part of the distance zoomed per line scrolled
+    pub distance_limits: (f32, f32),
+    pub pitch_limits: (f32, f32),
+    pub rotate_button: MouseButton,
+    pub pan_button: MouseButton,
+
+    rotating: bool,
+    panning: bool,
+    cursor: Option<(f64, f64)>,
+    last_frame: Option<Instant>,
+
+    pub subspace: Transform,
+    active: bool,
+
+    script: Option<Box<Script<Self>>>,
+
+    children: Vec<Arc<RwLock<dyn GameObject>>>,
+}
+
+impl std::fmt::Debug for OrbitCamera {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OrbitCamera")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("parent", &self.parent)
+            .field("main", &self.main)
+            .field("fov", &self.fov)
+            .field("near_plane", &self.near_plane)
+            .field("far_plane", &self.far_plane)
+            .field("aspect_ratio", &self.aspect_ratio)
+            .field("fixed_aspect_ratio", &self.fixed_aspect_ratio)
+            .field("target", &self.target)
+            .field("goal", &self.goal)
+            .field("current", &self.current)
+            .field("damping", &self.damping)
+            .field("subspace", &self.subspace)
+            .field("active", &self.active)
+            .field("script", &self.script)
+            .field("children", &self.children).finish()
+    }
+}
+
+impl Clone for OrbitCamera {
+    fn clone(&self) -> Self {
+        let id = self.id.get_system().take();
+        OrbitCamera{
+            id,
+            name: self.name.clone(),
+            parent: self.parent.clone(),
+            subspace: self.subspace.clone(),
+            active: self.active,
+            script: self.script.clone(),
+            children: Vec::new(), // see game_object::instantiate for copying children
+            main: self.main,
+            fov: self.fov,
+            near_plane: self.near_plane,
+            far_plane: self.far_plane,
+            aspect_ratio: self.aspect_ratio,
+            fixed_aspect_ratio: self.fixed_aspect_ratio,
+            target: self.target.clone(),
+            last_target: self.last_target,
+            goal: self.goal,
+            current: self.current,
+            damping: self.damping,
+            rotate_speed: self.rotate_speed,
+            pan_speed: self.pan_speed,
+            zoom_speed: self.zoom_speed,
+            distance_limits: self.distance_limits,
+            pitch_limits: self.pitch_limits,
+            rotate_button: self.rotate_button,
+            pan_button: self.pan_button,
+            rotating: false,
+            panning: false,
+            cursor: None,
+            last_frame: None,
+        }
+    }
+}
+
+impl PartialEq for OrbitCamera{
+    fn eq(&self, other: &Self) -> bool {
+        self.get_id() == other.get_id()
+    }
+}
+
+impl OrbitCamera {
+    /// Creates an orbit camera. Fails if the field of view, distance or aspect ratio is not positive or the
+    /// far plane is not beyond the near plane. The camera is placed around its target on its first update.
+    /// # Arguments
+    /// * `target` - What the camera circles around.
+    /// * `yaw` - The angle in radians around the world y axis. At 0 the camera is on the +z side of its target.
+    /// * `pitch` - The angle in radians above the horizon.
+    /// * `distance` - The distance from the focus.
+    /// * `fov` - The vertical field of view in radians.
+    /// * `near_plane` - The distance in front of the camera where the view starts.
+    /// * `far_plane` - The distance in front of the camera where the view ends.
+    /// * `aspect_ratio` - The width of the view divided by its height. It follows the size of the image the camera renders to. See `Camera::set_aspect_ratio_fixed`.
+    /// * `script` - The script of the camera. See `OrbitCamera::orbit_script`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+            name: Option<&str>,
+
+            main: bool,
+
+            parent: Option<Arc<RwLock<dyn GameObject>>>,
+
+            target: OrbitTarget,
+            yaw: f32,
+            pitch: f32,
+            distance: f32,
+
+            fov: f32,
+            near_plane: f32,
+            far_plane: f32,
+            aspect_ratio: f32,
+
+            script: Option<Box<Script<Self>>>,
+
+            engine_globals: EngineGlobals
+            ) -> Result<Arc<RwLock<Self>>, &'static str> {
+        if fov <= 0.0 || !fov.is_finite() {
+            return Err("The field of view of a camera must be positive.");
+        }
+        if distance <= 0.0 || !distance.is_finite() {
+            return Err("The distance of an orbit camera must be positive.");
+        }
+        if aspect_ratio <= 0.0 || !aspect_ratio.is_finite() {
+            return Err("The aspect ratio of a camera must be positive.");
+        }
+        if near_plane <= 0.0 || far_plane <= near_plane {
+            return Err("The far plane must be further than the near plane which must be in front of the camera.");
+        }
+
+        let id = engine_globals.id_system.take();
+        let pitch_limits = (-1.5, 1.5); // short of straight up or down where yaw stops making sense
+        let goal = Orbit {
+            yaw,
+            pitch: pitch.max(pitch_limits.0).min(pitch_limits.1),
+            distance,
+            pan: Vector3(0.0, 0.0, 0.0),
+        };
+
+        Ok(Arc::new(RwLock::new( OrbitCamera {
+            name: match name {
+                Some(name) => name.to_string(),
+                None => String::from("orbit_camera_") + id.to_string().as_str()
+            },
+            id,
+            parent: match parent {
+                Some(game_object) => {
+                    ParentWrapper::GameObject(game_object)
+                },
+                None => {
+                    ParentWrapper::Scene(engine_globals.scene)
+                }
+            }.downgrade(),
+
+            main,
+            fov,
+            near_plane,
+            far_plane,
+            aspect_ratio,
+            fixed_aspect_ratio: false,
+
+            target,
+            last_target: Vector3(0.0, 0.0, 0.0),
+            goal,
+            current: goal,
+
+            damping: 0.85,
+            rotate_speed: 0.005,
+            pan_speed: 0.001,
+            zoom_speed: 0.1,
+            distance_limits: (near_plane, far_plane * 0.5),
+            pitch_limits,
+            rotate_button: MouseButton::Left,
+            pan_button: MouseButton::Middle,
+
+            rotating: false,
+            panning: false,
+            cursor: None,
+            last_frame: None,
+
+            subspace: Transform::new(Space::new(None, None, None)),
+            active: true,
+
+            script,
+
+            children: Vec::new()
+        })))
+    }
+
+    /// Creates a script that orbits, pans and zooms the camera with the mouse and updates it every frame.
+    pub fn orbit_script() -> Box<Script<Self>> {
+        Script::new_boxed(Box::pin(orbit_start), Box::pin(orbit_frame), Some(Box::pin(orbit_event_handler)))
+    }
+
+    /// Gets what the camera circles around.
+    pub fn get_target(&self) -> OrbitTarget {
+        self.target.clone()
+    }
+
+    /// Changes what the camera circles around. The camera eases over to the new target.
+    pub fn set_target(&mut self, target: OrbitTarget) {
+        // keep looking at the old focus and let the pan ease it over to the new target
+        let focus = self.last_target + self.current.pan;
+        self.target = target;
+        self.last_target = self.target_position();
+        self.current.pan = focus - self.last_target;
+    }
+
+    /// Gets the point the camera is heading to look at in world space. That is the target moved by the pan.
+    pub fn get_focus(&self) -> Vector3<f32> {
+        self.target_position() + self.goal.pan
+    }
+
+    /// Gets the angle in radians around the world y axis the camera is heading to.
+    pub fn get_yaw(&self) -> f32 {
+        self.goal.yaw
+    }
+
+    /// Sets the angle in radians around the world y axis.
+    pub fn set_yaw(&mut self, yaw: f32) {
+        self.goal.yaw = yaw;
+    }
+
+    /// Gets the angle in radians above the horizon the camera is heading to.
+    pub fn get_pitch(&self) -> f32 {
+        self.goal.pitch
+    }
+
+    /// Sets the angle in radians above the horizon. It is kept within the pitch limits.
+    pub fn set_pitch(&mut self, pitch: f32) {
+        self.goal.pitch = pitch.max(self.pitch_limits.0).min(self.pitch_limits.1);
+    }
+
+    /// Gets the distance from the focus the camera is heading to.
+    pub fn get_distance(&self) -> f32 {
+        self.goal.distance
+    }
+
+    /// Sets the distance from the focus. It is kept within the distance limits.
+    pub fn set_distance(&mut self, distance: f32) -> Result<(), &'static str> {
+        if distance <= 0.0 || !distance.is_finite() {
+            return Err("The distance of an orbit camera must be positive.");
+        }
+        self.goal.distance = distance.max(self.distance_limits.0).min(self.distance_limits.1);
+        Ok(())
+    }
+
+    /// Gets how far the focus is moved from the target in world space.
+    pub fn get_pan(&self) -> Vector3<f32> {
+        self.goal.pan
+    }
+
+    /// Moves the focus away from the target in world space. Set it to zero to look at the target again.
+    pub fn set_pan(&mut self, pan: Vector3<f32>) {
+        self.goal.pan = pan;
+    }
+
+    /// Turns the camera around the focus.
+    /// # Arguments
+    /// * `yaw` - The angle in radians to turn around the world y axis.
+    /// * `pitch` - The angle in radians to raise the camera by.
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        self.set_yaw(self.goal.yaw + yaw);
+        self.set_pitch(self.goal.pitch + pitch);
+    }
+
+    /// Moves the focus across the view.
+    /// # Arguments
+    /// * `right` - How far to move the focus to the right of the view.
+    /// * `up` - How far to move the focus up the view.
+    pub fn pan(&mut self, right: f32, up: f32) {
+        let rotation = self.goal.rotation();
+        self.goal.pan = self.goal.pan + rotate(rotation, Vector3(right, up, 0.0));
+    }
+
+    /// Moves the camera closer to the focus by a factor. e.g. 2 halves the distance.
+    pub fn zoom(&mut self, factor: f32) -> Result<(), &'static str> {
+        if factor <= 0.0 || !factor.is_finite() {
+            return Err("The zoom must be positive.");
+        }
+        self.set_distance(self.goal.distance / factor)
+    }
+
+    /// Gets the vertical field of view in radians.
+    pub fn get_fov(&self) -> f32 {
+        self.fov
+    }
+
+    /// Sets the vertical field of view in radians.
+    pub fn set_fov(&mut self, fov: f32) -> Result<(), &'static str> {
+        if fov <= 0.0 || !fov.is_finite() {
+            return Err("The field of view of a camera must be positive.");
+        }
+        self.fov = fov;
+        Ok(())
+    }
+
+    /// Sets the distances in front of the camera where the view starts and ends.
+    pub fn set_planes(&mut self, near_plane: f32, far_plane: f32) -> Result<(), &'static str> {
+        if near_plane <= 0.0 || far_plane <= near_plane {
+            return Err("The far plane must be further than the near plane which must be in front of the camera.");
+        }
+        self.near_plane = near_plane;
+        self.far_plane = far_plane;
+        Ok(())
+    }
+
+    /// Reacts to the mouse. Dragging with the rotate button orbits, dragging with the pan button pans and the wheel zooms.
+    /// Call it from the event handler of the camera's script.
+    pub fn handle_event(&mut self, event: &Event<'static, UserEvent<Arc<dyn Any + Send + Sync>>>) {
+        if let Event::WindowEvent { event, .. } = event {
+            match event {
+                WindowEvent::MouseInput { state, button, .. } => {
+                    let pressed = *state == ElementState::Pressed;
+                    if *button == self.rotate_button {
+                        self.rotating = pressed;
+                    }
+                    if *button == self.pan_button {
+                        self.panning = pressed;
+                    }
+                },
+                WindowEvent::CursorMoved { position, .. } => {
+                    if let Some((x, y)) = self.cursor {
+                        let (dx, dy) = ((position.x - x) as f32, (position.y - y) as f32);
+                        if self.rotating {
+                            // the scene follows the cursor
+                            self.orbit(-dx * self.rotate_speed, dy * self.rotate_speed);
+                        }
+                        if self.panning {
+                            let scale = self.pan_speed * self.goal.distance;
+                            self.pan(-dx * scale, dy * scale);
+                        }
+                    }
+                    self.cursor = Some((position.x, position.y));
+                },
+                WindowEvent::CursorLeft { .. } | WindowEvent::Focused(false) => {
+                    self.cursor = None;
+                    self.rotating = false;
+                    self.panning = false;
+                },
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let lines = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => *y,
+                        MouseScrollDelta::PixelDelta(position) => position.y as f32 / 20.0,
+                    };
+                    let factor = (1.0 + self.zoom_speed).powf(lines);
+                    if factor.is_finite() && factor > 0.0 {
+                        self.zoom(factor).unwrap();
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /// Eases the camera towards its goal and places it around the focus. Call it every frame.
+    /// # Arguments
+    /// * `delta` - The seconds since the last update.
+    pub fn update(&mut self, delta: f32) {
+        let amount = 1.0 - self.damping.clamp(0.0, 1.0).powf(delta * 60.0);
+        self.current = self.current.approach(&self.goal, amount);
+        self.place();
+    }
+
+    /// Moves the camera straight to its goal without easing.
+    pub fn snap(&mut self) {
+        self.current = self.goal;
+        self.place();
+    }
+
+    /// Places the camera around the focus according to the current orbit.
+    fn place(&mut self) {
+        self.last_target = self.target_position();
+        let rotation = self.current.rotation();
+        let focus = self.last_target + self.current.pan;
+        self.set_world_position(focus + rotate(rotation, Vector3(0.0, 0.0, self.current.distance)));
+        self.set_world_rotation(rotation);
+    }
+
+    fn target_position(&self) -> Vector3<f32> {
+        match &self.target {
+            OrbitTarget::Point(point) => *point,
+            OrbitTarget::GameObject(game_object) => game_object.upgrade()
+                .map(|game_object| game_object.read().unwrap().world_position())
+                .unwrap_or(self.last_target),
+        }
+    }
+
+    /// \[backend\] Describes the OrbitCamera for saving. See `scene::serialization`.
+    /// A target GameObject is saved as the point it was at.
+    pub(crate) fn describe(&self) -> GameObjectKind {
+        let target = self.target_position();
+        GameObjectKind::OrbitCamera {
+            main: self.main,
+            target: [target.0, target.1, target.2],
+            yaw: self.goal.yaw,
+            pitch: self.goal.pitch,
+            distance: self.goal.distance,
+            damping: self.damping,
+            fov: self.fov,
+            near_plane: self.near_plane,
+            far_plane: self.far_plane,
+            aspect_ratio: self.aspect_ratio,
+            fixed_aspect_ratio: self.fixed_aspect_ratio,
+        }
+    }
+}
+
+fn orbit_start(this: Arc<RwLock<OrbitCamera>>, _: EngineGlobals) -> BoxFuture<'static, Swap> {
+    Box::pin(async move {
+        let mut this = this.write().unwrap();
+        this.last_frame = Some(Instant::now());
+        this.snap();
+        Swap::None
+    })
+}
+
+fn orbit_frame(this: Arc<RwLock<OrbitCamera>>, _: EngineGlobals) -> BoxFuture<'static, Swap> {
+    Box::pin(async move {
+        let mut this = this.write().unwrap();
+        let now = Instant::now();
+        let delta = this.last_frame.map_or(0.0, |last_frame| (now - last_frame).as_secs_f32());
+        this.last_frame = Some(now);
+        this.update(delta);
+        Swap::None
+    })
+}
+
+fn orbit_event_handler(this: Arc<RwLock<OrbitCamera>>, _: EngineGlobals, event: Event<'static, UserEvent<Arc<dyn Any + Send + Sync>>>) -> BoxFuture<'static, Swap> {
+    Box::pin(async move {
+        this.write().unwrap().handle_event(&event);
+        Swap::None
+    })
+}
+
+impl Camera for OrbitCamera {
+    fn as_any(&self) -> &dyn Any { self }
+    fn as_gameobject(&self) -> &dyn GameObject { self }
+
+    fn cast_gameobject_arc_rwlock(&self, this: Arc<RwLock<dyn Camera>>) -> Arc<RwLock<dyn GameObject>> {
+        let this= Arc::into_raw(this).cast::<RwLock<Self>>();
+        let this = unsafe { Arc::from_raw(this) };
+        this as Arc<RwLock<dyn GameObject>>
+    }
+
+    fn is_main(&self) -> bool {
+        self.main
+    }
+
+    fn get_aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    fn set_aspect_ratio(&mut self, aspect_ratio: f32) -> Result<(), &'static str> {
+        if aspect_ratio <= 0.0 || !aspect_ratio.is_finite() {
+            return Err("The aspect ratio of a camera must be positive.");
+        }
+        self.aspect_ratio = aspect_ratio;
+        Ok(())
+    }
+
+    fn is_aspect_ratio_fixed(&self) -> bool {
+        self.fixed_aspect_ratio
+    }
+
+    fn set_aspect_ratio_fixed(&mut self, fixed: bool) {
+        self.fixed_aspect_ratio = fixed;
+    }
+
+    fn get_z_step (&self, z_buffer_size: usize) -> f32 {
+        ((self.far_plane / self.near_plane) * 0.5).powi(z_buffer_size as i32)
+    }
+
+    fn build_projection(&self) -> Matrix4<f32> {
+        let half_h = self.near_plane * (self.fov * 0.5).tan();
+        let half_w = half_h * self.aspect_ratio;
+
+        Matrix4::new(
+            [ self.near_plane / half_w,                       0.0,                                                                      0.0,                                                                            0.0],
+            [                      0.0, self.near_plane / -half_h, /* <- flipped y to account for vulkano axes */                       0.0,                                                                            0.0],
+            [                      0.0,                       0.0, -(self.near_plane + self.far_plane) / (self.far_plane - self.near_plane), (-2.0 * self.far_plane * self.near_plane) / (self.far_plane - self.near_plane)],
+            [                      0.0,                       0.0,                                                                     -1.0,                                                                           0.0]
+        )
+    }
+
+    fn build_viewspace(&self) -> Matrix4<f32> {
+        self.get_inversed_subspace().build()
+    }
+
+    fn create_uniforms(&self) -> vs_draw::ty::Camera {
+        vs_draw::ty::Camera {
+            to_view: self.build_viewspace().transpose().into(),
+            view_to_screen: self.build_projection().transpose().into()
+        }
+    }
+}
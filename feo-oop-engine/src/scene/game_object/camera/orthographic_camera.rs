@@ -0,0 +1,363 @@
+//! Orthographic Camera GameObject that can capture a scene without perspective
+//!
+//! Objects appear the same size however far they are from the camera which suits top-down games,
+//! editors and UI. The view is a box in front of the camera as tall as its height and as wide as its
+//! height times its aspect ratio. Zooming in shrinks the box.
+//!
+//! ## Example
+//! ```no_run
+//! # use feo_oop_engine::scene::game_object::camera::orthographic_camera::OrthographicCamera;
+//! # use feo_math::{linear_algebra::vector3::Vector3, rotation::quaternion::Quaternion};
+//! # fn f(engine_globals: feo_oop_engine::scripting::globals::EngineGlobals) {
+//! // looking straight down at a 20 unit tall area
+//! let camera = OrthographicCamera::new(
+//!     Some("top down camera"),
+//!     true,
+//!     None,
+//!     Some(Vector3(0.0, 50.0, 0.0)),
+//!     Some(Quaternion::new_axis_angle(Vector3(1.0, 0.0, 0.0), -std::f32::consts::FRAC_PI_2)),
+//!     None,
+//!     20.0,
+//!     0.1,
+//!     100.0,
+//!     16.0 / 9.0,
+//!     None,
+//!     engine_globals.clone()
+//! ).unwrap();
+//! engine_globals.scene.write().unwrap().set_main_camera(camera);
+//! # }
+//! ```
+//!
+use {
+    super::{
+        Camera,
+        super::{
+            GameObject,
+            light::Light,
+        }
+    },
+    crate::{
+        registration::{
+            relation::{
+                Child, Parent,
+                ParentWrapper, WeakParentWrapper
+            },
+            named::Named,
+            id::ID
+        },
+        scripting::{
+            Script,
+            executor::Spawner,
+            Scriptable,
+            globals::{
+                EngineGlobals,
+                Global
+            }
+        },
+        components::{
+            triangle_mesh::TriangleMesh,
+            transform::Transform,
+        },
+        graphics::{
+            Drawable,
+            draw_pass_manager::DrawPassManager,
+            lighting_pass_manager::LightingPassManager,
+        },
+        event::UserEvent,
+        scene::serialization::GameObjectKind,
+        shaders::vs_draw,
+    },
+    feo_math::{
+        linear_algebra::{
+            vector3::Vector3,
+            matrix4::Matrix4
+        },
+        utils::space::Space,
+        rotation::quaternion::Quaternion
+    },
+    std::{
+        any::Any,
+        sync::{
+            Arc,
+            RwLock
+        },
+        mem
+    },
+    winit::event::Event,
+};
+
+#[derive(Scriptable, GameObject, Parent, Child, Named, Drawable)]
+#[camera]
+pub struct OrthographicCamera{
+    id: ID,
+    name: String,
+    parent: WeakParentWrapper,
+
+    main: bool,
+
+    height: f32, // of the view at a zoom of 1
+    zoom: f32,
+    near_plane: f32,
+    far_plane: f32,
+    aspect_ratio: f32,
+    fixed_aspect_ratio: bool, // letterboxes rather than following the size of the image rendered to
+
+    pub subspace: Transform,
+    active: bool,
+
+    script: Option<Box<Script<Self>>>,
+
+    children: Vec<Arc<RwLock<dyn GameObject>>>,
+}
+
+impl std::fmt::Debug for OrthographicCamera {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OrthographicCamera")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("parent", &self.parent)
+            .field("main", &self.main)
+            .field("height", &self.height)
+            .field("zoom", &self.zoom)
+            .field("near_plane", &self.near_plane)
+            .field("far_plane", &self.far_plane)
+            .field("aspect_ratio", &self.aspect_ratio)
+            .field("fixed_aspect_ratio", &self.fixed_aspect_ratio)
+            .field("subspace", &self.subspace)
+            .field("active", &self.active)
+            .field("script", &self.script)
+            .field("children", &self.children).finish()
+    }
+}
+
+impl Clone for OrthographicCamera {
+    fn clone(&self) -> Self {
+        let id = self.id.get_system().take();
+        OrthographicCamera{
+            id,
+            name: self.name.clone(),
+            parent: self.parent.clone(),
+            subspace: self.subspace.clone(),
+            active: self.active,
+            script: self.script.clone(),
+            children: Vec::new(), // see game_object::instantiate for copying children
+            main: self.main,
+            height: self.height,
+            zoom: self.zoom,
+            near_plane: self.near_plane,
+            far_plane: self.far_plane,
+            aspect_ratio: self.aspect_ratio,
+            fixed_aspect_ratio: self.fixed_aspect_ratio,
+        }
+    }
+}
+
+impl PartialEq for OrthographicCamera{
+    fn eq(&self, other: &Self) -> bool {
+        self.get_id() == other.get_id()
+    }
+}
+
+impl OrthographicCamera {
+    /// Creates an orthographic camera. Fails if the height or aspect ratio is not positive or the far plane is not beyond the near plane.
+    /// # Arguments
+    /// * `height` - The height of the view in world units.
+    /// * `near_plane` - The distance in front of the camera where the view starts. May be negative.
+    /// * `far_plane` - The distance in front of the camera where the view ends.
+    /// * `aspect_ratio` - The width of the view divided by its height. It follows the size of the image the camera renders to. See `Camera::set_aspect_ratio_fixed`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+            name: Option<&str>,
+
+            main: bool,
+
+            parent: Option<Arc<RwLock<dyn GameObject>>>,
+
+            position: Option<Vector3<f32>>,
+            rotation: Option<Quaternion<f32>>,
+            scale_factor: Option<Vector3<f32>>,
+
+            height: f32,
+            near_plane: f32,
+            far_plane: f32,
+            aspect_ratio: f32,
+
+            script: Option<Box<Script<Self>>>,
+
+            engine_globals: EngineGlobals
+            ) -> Result<Arc<RwLock<Self>>, &'static str> {
+        if height <= 0.0 || !height.is_finite() {
+            return Err("The height of an orthographic camera must be positive.");
+        }
+        if aspect_ratio <= 0.0 || !aspect_ratio.is_finite() {
+            return Err("The aspect ratio of a camera must be positive.");
+        }
+        if far_plane <= near_plane {
+            return Err("The far plane must be further than the near plane.");
+        }
+
+        let id = engine_globals.id_system.take();
+        let subspace = Transform::new(Space::new(position, rotation, scale_factor));
+
+        Ok(Arc::new(RwLock::new( OrthographicCamera {
+            name: match name {
+                Some(name) => name.to_string(),
+                None => String::from("orthographic_camera_") + id.to_string().as_str()
+            },
+            id,
+            parent: match parent {
+                Some(game_object) => {
+                    ParentWrapper::GameObject(game_object)
+                },
+                None => {
+                    ParentWrapper::Scene(engine_globals.scene)
+                }
+            }.downgrade(),
+
+            main,
+            height,
+            zoom: 1.0,
+            near_plane,
+            far_plane,
+            aspect_ratio,
+            fixed_aspect_ratio: false,
+            subspace,
+            active: true,
+
+            script,
+
+            children: Vec::new()
+        })))
+    }
+
+    /// Gets the height of the view in world units at a zoom of 1.
+    pub fn get_height(&self) -> f32 {
+        self.height
+    }
+
+    /// Sets the height of the view in world units at a zoom of 1. The width follows from the aspect ratio.
+    pub fn set_height(&mut self, height: f32) -> Result<(), &'static str> {
+        if height <= 0.0 || !height.is_finite() {
+            return Err("The height of an orthographic camera must be positive.");
+        }
+        self.height = height;
+        Ok(())
+    }
+
+    /// Gets the width of the view in world units at a zoom of 1.
+    pub fn get_width(&self) -> f32 {
+        self.height * self.aspect_ratio
+    }
+
+    /// Sets the width of the view in world units at a zoom of 1. The height follows from the aspect ratio.
+    pub fn set_width(&mut self, width: f32) -> Result<(), &'static str> {
+        self.set_height(width / self.aspect_ratio)
+    }
+
+    /// Gets how far the camera is zoomed in. 2 shows half the width and height.
+    pub fn get_zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Sets how far the camera is zoomed in. 2 shows half the width and height.
+    pub fn set_zoom(&mut self, zoom: f32) -> Result<(), &'static str> {
+        if zoom <= 0.0 || !zoom.is_finite() {
+            return Err("The zoom must be positive.");
+        }
+        self.zoom = zoom;
+        Ok(())
+    }
+
+    /// Sets the distances in front of the camera where the view starts and ends.
+    pub fn set_planes(&mut self, near_plane: f32, far_plane: f32) -> Result<(), &'static str> {
+        if far_plane <= near_plane {
+            return Err("The far plane must be further than the near plane.");
+        }
+        self.near_plane = near_plane;
+        self.far_plane = far_plane;
+        Ok(())
+    }
+
+    /// Gets the width and height of what the camera currently sees in world units.
+    pub fn get_view_size(&self) -> (f32, f32) {
+        let height = self.height / self.zoom;
+        (height * self.aspect_ratio, height)
+    }
+
+    /// \[backend\] Describes the OrthographicCamera for saving. See `scene::serialization`.
+    pub(crate) fn describe(&self) -> GameObjectKind {
+        GameObjectKind::OrthographicCamera {
+            main: self.main,
+            height: self.height,
+            zoom: self.zoom,
+            near_plane: self.near_plane,
+            far_plane: self.far_plane,
+            aspect_ratio: self.aspect_ratio,
+            fixed_aspect_ratio: self.fixed_aspect_ratio,
+        }
+    }
+}
+
+impl Camera for OrthographicCamera {
+    fn as_any(&self) -> &dyn Any { self }
+    fn as_gameobject(&self) -> &dyn GameObject { self }
+
+    fn cast_gameobject_arc_rwlock(&self, this: Arc<RwLock<dyn Camera>>) -> Arc<RwLock<dyn GameObject>> {
+        let this= Arc::into_raw(this).cast::<RwLock<Self>>();
+        let this = unsafe { Arc::from_raw(this) };
+        this as Arc<RwLock<dyn GameObject>>
+    }
+
+    fn is_main(&self) -> bool {
+        self.main
+    }
+
+    fn get_aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    fn set_aspect_ratio(&mut self, aspect_ratio: f32) -> Result<(), &'static str> {
+        if aspect_ratio <= 0.0 || !aspect_ratio.is_finite() {
+            return Err("The aspect ratio of a camera must be positive.");
+        }
+        self.aspect_ratio = aspect_ratio;
+        Ok(())
+    }
+
+    fn is_aspect_ratio_fixed(&self) -> bool {
+        self.fixed_aspect_ratio
+    }
+
+    fn set_aspect_ratio_fixed(&mut self, fixed: bool) {
+        self.fixed_aspect_ratio = fixed;
+    }
+
+    fn get_z_step (&self, z_buffer_size: usize) -> f32 {
+        // the depth is spread evenly over the 0 to 1 depth range between the planes
+        (self.far_plane - self.near_plane) / 2.0f32.powi(z_buffer_size as i32)
+    }
+
+    fn build_projection(&self) -> Matrix4<f32> {
+        let (width, height) = self.get_view_size();
+        let (half_w, half_h) = (width * 0.5, height * 0.5);
+
+        // maps the near plane to a depth of 0 and the far plane to 1 as vulkan expects
+        Matrix4::new(
+            [ 1.0 / half_w,           0.0,                                              0.0,                                                      0.0],
+            [          0.0, 1.0 / -half_h, /* <- flipped y to account for vulkano axes */ 0.0,                                                      0.0],
+            [          0.0,           0.0, -1.0 / (self.far_plane - self.near_plane), -self.near_plane / (self.far_plane - self.near_plane)],
+            [          0.0,           0.0,                                              0.0,                                                      1.0]
+        )
+    }
+
+    fn build_viewspace(&self) -> Matrix4<f32> {
+        self.get_inversed_subspace().build()
+    }
+
+    fn create_uniforms(&self) -> vs_draw::ty::Camera {
+        vs_draw::ty::Camera {
+            to_view: self.build_viewspace().transpose().into(),
+            view_to_screen: self.build_projection().transpose().into()
+        }
+    }
+}
@@ -12,7 +12,7 @@ use {
         registration::{
             relation::{
                 Child, Parent,
-                ParentWrapper
+                ParentWrapper, WeakParentWrapper
             },
             named::Named,
             id::ID,
@@ -29,7 +29,8 @@ use {
             lighting_pass_manager::LightingPassManager,
         },
         event::UserEvent,
-        components::triangle_mesh::TriangleMesh
+        scene::serialization::GameObjectKind,
+        components::{triangle_mesh::TriangleMesh, transform::Transform}
     },
     std::{
         any::Any,
@@ -48,11 +49,12 @@ use {
 pub struct Group {
     pub id: ID,
     pub name: String,
-    pub parent: ParentWrapper,
+    pub parent: WeakParentWrapper,
 
-    pub subspace: Space,
+    pub subspace: Transform,
 
     pub visible: bool,
+    pub active: bool, // disables the scripts and rendering of the GameObject and its children
 
     pub script: Option<Box<Script<Self>>>,
 
@@ -67,6 +69,7 @@ impl std::fmt::Debug for Group {
             .field("parent", &self.parent)
             .field("subspace", &self.subspace)
             .field("visible", &self.visible)
+            .field("active", &self.active)
             .field("script", &self.script)
             .field("children", &self.children).finish()
     }
@@ -80,12 +83,10 @@ impl Clone for Group {
             name: self.name.clone(),
             parent: self.parent.clone(),
             visible: self.visible,
-            subspace: self.subspace,
+            active: self.active,
+            subspace: self.subspace.clone(),
             script: self.script.clone(),
-            children: self.children.clone().into_iter().map(|_child| {
-                // Dangerous
-                todo!();
-            }).collect::<Vec<Arc<RwLock<dyn GameObject>>>>(),
+            children: Vec::new(), // see game_object::instantiate for copying children
         }
     }
 }
@@ -123,11 +124,17 @@ impl Group {
             parent: match parent {
                 Some(game_object) => ParentWrapper::GameObject(game_object),
                 None => ParentWrapper::Scene(engine_globals.scene)
-            },
-            subspace: Space::new(position, rotation, scale_factor),
+            }.downgrade(),
+            subspace: Transform::new(Space::new(position, rotation, scale_factor)),
             visible,
+            active: true,
             script,
             children: Vec::new()
         }))
     }
+
+    /// \[backend\] Describes the Group for saving. See `scene::serialization`.
+    pub(crate) fn describe(&self) -> GameObjectKind {
+        GameObjectKind::Group { visible: self.visible }
+    }
 }
\ No newline at end of file
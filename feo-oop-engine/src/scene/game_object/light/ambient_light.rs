@@ -6,7 +6,7 @@ use {
     crate::{
         registration::{
             relation::{
-                ParentWrapper, 
+                ParentWrapper, WeakParentWrapper,
                 Parent, Child
             }, 
             named::Named,
@@ -27,6 +27,7 @@ use {
         },
         components::{
             triangle_mesh::TriangleMesh,
+            transform::Transform,
             RGB, 
             ScreenPos
         },
@@ -34,7 +35,8 @@ use {
             fs_lighting_ambient,
             vs_lighting
         },
-        event::UserEvent
+        event::UserEvent,
+        scene::serialization::GameObjectKind,
     },
     vulkano::{
         buffer::{
@@ -97,12 +99,13 @@ pub struct AmbientLight {
     name: String,
     id: ID,
 
-    pub subspace: Space,
+    pub subspace: Transform,
+    active: bool,
 
     intensity: f32,
     color: RGB,
 
-    parent: ParentWrapper,
+    parent: WeakParentWrapper,
     children: Vec<Arc<RwLock<dyn GameObject>>>,
 
     script: Option<Box<Script<Self>>>,
@@ -114,6 +117,7 @@ impl std::fmt::Debug for AmbientLight {
             .field("name", &self.name)
             .field("id", &self.id)
             .field("subspace", &self.subspace)
+            .field("active", &self.active)
             .field("intensity", &self.intensity)
             .field("color", &self.color)
             .field("parent", &self.parent)
@@ -129,12 +133,10 @@ impl Clone for AmbientLight {
             id,
             name: self.name.clone(),
             parent: self.parent.clone(),
-            subspace: self.subspace,
+            subspace: self.subspace.clone(),
+            active: self.active,
             script: self.script.clone(),
-            children: self.children.clone().into_iter().map(|_child| {
-                // Dangerous
-                todo!();
-            }).collect::<Vec<Arc<RwLock<dyn GameObject>>>>(),
+            children: Vec::new(), // see game_object::instantiate for copying children
             intensity: self.intensity,
             color: self.color,
         }
@@ -167,18 +169,27 @@ impl AmbientLight {
 
             intensity,
             color,
-            subspace: Space::new(position, rotation, scale_factor),
+            subspace: Transform::new(Space::new(position, rotation, scale_factor)),
+            active: true,
 
             parent: match parent{
                 Some(game_object) => ParentWrapper::GameObject(game_object), 
                 None => ParentWrapper::Scene(engine_globals.scene),
-            },
+            }.downgrade(),
             children: Vec::new(),
 
             script
         }))
     }
 
+    /// \[backend\] Describes the AmbientLight for saving. See `scene::serialization`.
+    pub(crate) fn describe(&self) -> GameObjectKind {
+        GameObjectKind::AmbientLight {
+            intensity: self.intensity,
+            color: [self.color.r, self.color.g, self.color.b],
+        }
+    }
+
     fn draw<C, A, D> (
         &self, 
         graphics_system: GraphicsSystem, 
@@ -6,7 +6,7 @@ use {
     crate::{ 
         registration::{
             relation::{
-                ParentWrapper, 
+                ParentWrapper, WeakParentWrapper,
                 Parent, Child
             },
             named::Named,
@@ -31,10 +31,12 @@ use {
         },  
         components::{
             triangle_mesh::TriangleMesh,
+            transform::Transform,
             RGB, 
             ScreenPos
         },
-        event::UserEvent
+        event::UserEvent,
+        scene::serialization::GameObjectKind,
     },
     vulkano::{
         buffer::{
@@ -100,12 +102,13 @@ pub struct PointLight {
     name: String,
     id: ID,
 
-    pub subspace: Space,
+    pub subspace: Transform,
+    active: bool,
 
     intensity: f32,
     color: RGB,
 
-    parent: ParentWrapper,
+    parent: WeakParentWrapper,
     children: Vec<Arc<RwLock<dyn GameObject>>>,
 
     script: Option<Box<Script<Self>>>,
@@ -117,6 +120,7 @@ impl std::fmt::Debug for PointLight {
             .field("name", &self.name)
             .field("id", &self.id)
             .field("subspace", &self.subspace)
+            .field("active", &self.active)
             .field("intensity", &self.intensity)
             .field("color", &self.color)
             .field("parent", &self.parent)
@@ -132,12 +136,10 @@ impl Clone for PointLight {
             id,
             name: self.name.clone(),
             parent: self.parent.clone(),
-            subspace: self.subspace,
+            subspace: self.subspace.clone(),
+            active: self.active,
             script: self.script.clone(),
-            children: self.children.clone().into_iter().map(|_child| {
-                // Dangerous
-                todo!();
-            }).collect::<Vec<Arc<RwLock<dyn GameObject>>>>(),
+            children: Vec::new(), // see game_object::instantiate for copying children
             intensity: self.intensity,
             color: self.color,
         }
@@ -170,18 +172,27 @@ impl PointLight {
 
             intensity,
             color,
-            subspace: Space::new(position, rotation, scale_factor),
+            subspace: Transform::new(Space::new(position, rotation, scale_factor)),
+            active: true,
 
             parent: match parent{
                 Some(game_object) => ParentWrapper::GameObject(game_object), 
                 None => ParentWrapper::Scene(engine_globals.scene),
-            },
+            }.downgrade(),
             children: Vec::new(),
 
             script,
         }))
     }
 
+    /// \[backend\] Describes the PointLight for saving. See `scene::serialization`.
+    pub(crate) fn describe(&self) -> GameObjectKind {
+        GameObjectKind::PointLight {
+            intensity: self.intensity,
+            color: [self.color.r, self.color.g, self.color.b],
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn draw<N, C, S, D>(
         &self,
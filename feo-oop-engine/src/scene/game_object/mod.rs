@@ -8,6 +8,7 @@ pub mod light;
 pub mod camera;
 pub mod group;
 pub mod obj;
+pub mod pool;
 
 use {
     self::{
@@ -17,19 +18,22 @@ use {
 
     crate::{
         registration::{
-            relation::{Child, Parent},
+            relation::{Child, Parent, ParentWrapper},
             id::ID,
         },
         scripting::Scriptable,
         graphics::{
             Drawable
         },
+        components::transform::{Transform, WorldTransform},
     },
     feo_math::{
         utils::space::Space,
+        linear_algebra::vector3::Vector3,
+        rotation::quaternion::Quaternion,
     },
     std::{
-        any::Any,
+        any::{Any, TypeId},
         sync::{
             Arc, 
             RwLock
@@ -47,12 +51,110 @@ pub trait GameObject:
         Any + 'static + 
         Send + Sync {
     fn as_any(&self) -> &dyn Any;
-    fn cast_camera_arc_rwlock(&self, this: Arc<RwLock<dyn GameObject>>) -> Result<Arc<RwLock<dyn Camera>>, ()>; 
-    fn cast_light_arc_rwlock(&self, this: Arc<RwLock<dyn GameObject>>) -> Result<Arc<RwLock<dyn Light>>, ()>; 
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    /// \[backend\] Casts the handle of this GameObject to a camera handle. Use `GameObjectHandle::as_camera` instead.
+    /// # Safety
+    /// `this` must be the handle of self.
+    unsafe fn cast_camera_arc_rwlock(&self, this: Arc<RwLock<dyn GameObject>>) -> Result<Arc<RwLock<dyn Camera>>, ()>; 
+    /// \[backend\] Casts the handle of this GameObject to a light handle. Use `GameObjectHandle::as_light` instead.
+    /// # Safety
+    /// `this` must be the handle of self.
+    unsafe fn cast_light_arc_rwlock(&self, this: Arc<RwLock<dyn GameObject>>) -> Result<Arc<RwLock<dyn Light>>, ()>; 
+    /// Gets the GameObject as a camera if it is one.
+    fn as_camera_ref(&self) -> Option<&dyn Camera>;
+    /// Gets the GameObject as a light if it is one.
+    fn as_light_ref(&self) -> Option<&dyn Light>;
 
     fn get_id(&self) -> ID;
-    fn get_subspace(&self) -> Space;
-    fn get_inversed_subspace(&self) -> Space;
+    /// Gets the name of the concrete type. e.g. "Obj"
+    fn type_name(&self) -> &'static str;
+
+    /// Checks if the GameObject itself is active. See `is_active_in_hierarchy`.
+    fn get_active(&self) -> bool;
+    /// Enables or disables the GameObject. The scripts of an inactive GameObject and of its children are paused
+    /// and they are not rendered. Their frame functions resume where they left off once it is enabled again.
+    /// Fails if the GameObject has no active field.
+    fn set_active(&mut self, active: bool) -> Result<(), &'static str>;
+
+    /// Checks if the GameObject and all of its parents are active.
+    ///
+    /// Takes a read lock on every parent so do not call it while holding a write lock on one.
+    fn is_active_in_hierarchy(&self) -> bool {
+        self.get_active() && match self.get_parent() {
+            Some(ParentWrapper::GameObject(game_object)) => game_object.read().unwrap().is_active_in_hierarchy(),
+            _ => true
+        }
+    }
+
+    /// Checks if the GameObject and all of its parents are visible and active, i.e. whether it is rendered.
+    ///
+    /// Takes a read lock on every parent so do not call it while holding a write lock on one.
+    fn is_visible_in_hierarchy(&self) -> bool {
+        self.get_active() && self.get_visible() && match self.get_parent() {
+            Some(ParentWrapper::GameObject(game_object)) => game_object.read().unwrap().is_visible_in_hierarchy(),
+            _ => true
+        }
+    }
+
+    fn get_transform(&self) -> &Transform;
+    fn get_transform_mut(&mut self) -> &mut Transform;
+
+    /// Sets the local space.
+    fn set_subspace(&mut self, subspace: Space);
+    /// Moves the local space by a vector in the parent space.
+    fn translate(&mut self, vector: Vector3<f32>);
+    /// Rotates the local space by a quaternion.
+    fn rotate(&mut self, rotation: Quaternion<f32>);
+    /// Scales the local space along its axes.
+    fn scale(&mut self, factor: Vector3<f32>);
+    /// Turns the -z axis to the target point in the parent space keeping the y axis as close to up as possible.
+    fn look_at(&mut self, target: Vector3<f32>, up: Vector3<f32>);
+    /// Turns towards looking at the target point in the parent space by no more than max_angle radians.
+    fn rotate_towards(&mut self, target: Vector3<f32>, max_angle: f32);
+
+    /// Gets the world transform of the parent.
+    fn get_parent_world_transform(&self) -> WorldTransform {
+        match self.get_parent() {
+            Some(ParentWrapper::GameObject(game_object)) => game_object.read().unwrap().get_world_transform(),
+            Some(ParentWrapper::Scene(scene)) => scene.read().unwrap().get_world_transform(),
+            None => WorldTransform::identity() // orphaned
+        }
+    }
+
+    /// Gets the cached world transform. It is rebuilt if this or any of its parents moved.
+    /// The parents are only read if anything moved since it was last checked.
+    fn get_world_transform(&self) -> WorldTransform {
+        self.get_transform().resolve_with(|| self.get_parent_world_transform())
+    }
+
+    /// Gets the local to world space.
+    fn get_subspace(&self) -> Space { self.get_world_transform().local_to_world }
+
+    /// Gets the world to local space.
+    fn get_inversed_subspace(&self) -> Space { self.get_world_transform().world_to_local }
+
+    /// Gets the position in world space.
+    fn world_position(&self) -> Vector3<f32> { self.get_subspace().center }
+
+    /// Gets the rotation in world space.
+    fn world_rotation(&self) -> Quaternion<f32> { self.get_subspace().rotation }
+
+    /// Moves the GameObject to a position in world space.
+    fn set_world_position(&mut self, position: Vector3<f32>) {
+        let parent = self.get_parent_world_transform().local_to_world;
+
+        // undo the parent's rotation and translation. see Space::join
+        let local = parent.rotation.reciprocal() * Quaternion::new_vector_real(position - parent.center, 0.0) * parent.rotation;
+        self.get_transform_mut().center = Vector3(local.0, local.1, local.2);
+    }
+
+    /// Turns the GameObject to a rotation in world space.
+    fn set_world_rotation(&mut self, rotation: Quaternion<f32>) {
+        let parent = self.get_parent_world_transform().local_to_world;
+
+        // undo the parent's rotation. see Space::join
+        self.get_transform_mut().rotation = parent.rotation.reciprocal() * rotation;
+    }
 }
 
 impl PartialEq for dyn GameObject{
@@ -67,15 +169,109 @@ impl fmt::Debug for dyn GameObject {
     }
 }
 
+/// Safe conversions of the handle of a GameObject.
+///
+/// These take a read lock on the GameObject so do not call them while holding a write lock on it.
+/// # Examples
+/// ```no_run
+/// # use feo_oop_engine::scene::game_object::{GameObjectHandle, obj::Obj};
+/// # let scene = feo_oop_engine::scene::Scene::new(None);
+/// let player = scene.read().unwrap().find_path("player").unwrap();
+/// if let Some(player) = player.downcast::<Obj>() {
+///     player.write().unwrap().visible = false;
+/// }
+/// ```
+pub trait GameObjectHandle {
+    /// Gets the handle of the concrete type. Returns None if the GameObject is of another type.
+    fn downcast<T: GameObject>(&self) -> Option<Arc<RwLock<T>>>;
+    /// Gets the handle as a camera. Returns None if the GameObject is not a camera.
+    fn as_camera(&self) -> Option<Arc<RwLock<dyn Camera>>>;
+    /// Gets the handle as a light. Returns None if the GameObject is not a light.
+    fn as_light(&self) -> Option<Arc<RwLock<dyn Light>>>;
+}
+
+impl GameObjectHandle for Arc<RwLock<dyn GameObject>> {
+    fn downcast<T: GameObject>(&self) -> Option<Arc<RwLock<T>>> {
+        if self.read().unwrap().as_any().type_id() != TypeId::of::<T>() {
+            return None;
+        }
+        
+        // The type was checked above.
+        let this = Arc::into_raw(self.clone()).cast::<RwLock<T>>();
+        Some(unsafe { Arc::from_raw(this) })
+    }
+
+    fn as_camera(&self) -> Option<Arc<RwLock<dyn Camera>>> {
+        // self is the handle of the GameObject
+        unsafe { self.read().unwrap().cast_camera_arc_rwlock(self.clone()) }.ok()
+    }
+
+    fn as_light(&self) -> Option<Arc<RwLock<dyn Light>>> {
+        // self is the handle of the GameObject
+        unsafe { self.read().unwrap().cast_light_arc_rwlock(self.clone()) }.ok()
+    }
+}
+
 /// Allows Box<dyn GameObject> to be clonable
 pub trait GameObjectBoxClone {
     fn clone_game_object(&self) -> Box<dyn GameObject>;
+    fn clone_game_object_arc_rwlock(&self) -> Arc<RwLock<dyn GameObject>>;
 }
 
 impl<T> GameObjectBoxClone for T where T: 'static + GameObject + Clone {
     fn clone_game_object(&self) -> Box<dyn GameObject> {
         Box::new(self.clone())
     }
+
+    fn clone_game_object_arc_rwlock(&self) -> Arc<RwLock<dyn GameObject>> {
+        Arc::new(RwLock::new(self.clone()))
+    }
+}
+
+/// Creates a deep copy of a GameObject and all of its children. Use this to spawn GameObjects from a template (prefab).
+///
+/// Every copy is given a fresh ID and the children of each copy are parented to it.
+/// Scripts are copied with fresh state. They run their start function again which builds their globals anew.
+/// Meshes are shared with the template rather than reloaded.
+///
+/// Note that like the GameObject constructors this does not add the copy to the parent's children.
+/// Do that using `Parent::add_child` or the swap system.
+/// # Arguments
+/// * `prefab` - The GameObject to copy.
+/// * `parent` - The parent of the copy.
+/// # Examples
+/// ```no_run
+/// # use feo_oop_engine::{scene::game_object::{instantiate, obj::Obj}, registration::relation::ParentWrapper};
+/// # fn f(bullet_prefab: std::sync::Arc<std::sync::RwLock<Obj>>, scene: std::sync::Arc<std::sync::RwLock<feo_oop_engine::scene::Scene>>) {
+/// let bullet = instantiate(&bullet_prefab, ParentWrapper::Scene(scene));
+/// # }
+/// ```
+pub fn instantiate<T>(prefab: &Arc<RwLock<T>>, parent: ParentWrapper) -> Arc<RwLock<T>> where T: GameObject + Clone {
+    let prefab = prefab.read().unwrap();
+    let copy = Arc::new(RwLock::new(prefab.clone()));
+    unsafe { copy.write().unwrap().set_parent(parent); }
+    instantiate_children(&*prefab, copy.clone());
+    copy
+}
+
+/// Creates a deep copy of a GameObject and all of its children without knowing its type.
+/// See `instantiate`.
+/// # Arguments
+/// * `prefab` - The GameObject to copy.
+/// * `parent` - The parent of the copy.
+pub fn instantiate_dyn(prefab: &Arc<RwLock<dyn GameObject>>, parent: ParentWrapper) -> Arc<RwLock<dyn GameObject>> {
+    let prefab = prefab.read().unwrap();
+    let copy = prefab.clone_game_object_arc_rwlock();
+    unsafe { copy.write().unwrap().set_parent(parent); }
+    instantiate_children(&*prefab, copy.clone());
+    copy
+}
+
+fn instantiate_children(prefab: &dyn GameObject, copy: Arc<RwLock<dyn GameObject>>) {
+    prefab.get_children().iter().for_each(|child| {
+        let child_copy = instantiate_dyn(child, ParentWrapper::GameObject(copy.clone()));
+        copy.write().unwrap().add_child(child_copy);
+    });
 }
 
 impl Clone for Box<dyn GameObject> {
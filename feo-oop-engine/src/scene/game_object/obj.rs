@@ -10,7 +10,7 @@ use {
     },
     crate::{
         registration::{
-            relation::{ParentWrapper, Child, Parent},
+            relation::{ParentWrapper, WeakParentWrapper, Child, Parent},
             named::Named,
             id::{
                 ID
@@ -37,7 +37,8 @@ use {
         },
         term_ui,
         event::UserEvent,
-        components::{material::Material, triangle_mesh::TriangleMesh}
+        components::{material::Material, texture::Texture, triangle_mesh::TriangleMesh, transform::Transform},
+        scene::serialization::{GameObjectKind, MaterialDescriptor},
     },
     feo_math::{
         utils::space::Space, 
@@ -57,6 +58,7 @@ use {
         descriptor::{
             descriptor_set::PersistentDescriptorSet,
         },
+        device::Queue,
         sync::GpuFuture
     },
     winit::event::Event
@@ -66,18 +68,23 @@ use {
 pub struct Obj {
     pub id: ID,
     pub name: String,
-    pub parent: ParentWrapper,
+    pub parent: WeakParentWrapper,
 
     pub visible: bool,
+    pub active: bool, // disables the scripts and rendering of the GameObject and its children
 
-    pub subspace: Space, // note is the subspace within the parent space
+    pub subspace: Transform, // note is the subspace within the parent space
 
     pub triangle_mesh: Vec<Arc<TriangleMesh>>,
+    pub mesh_path: Option<String>, // the obj file the mesh was loaded from
+    pub material_path: Option<(String, String)>, // the mtl file and material overriding the mesh's materials
     // pub material: Option<Material>, // object does not have material triangle mesh does
 
     pub script: Option<Box<Script<Self>>>,
 
     pub children: Vec<Arc<RwLock<dyn GameObject>>>,
+
+    pub(crate) generated: bool, // created while loading the mesh of the parent
 }
 
 impl std::fmt::Debug for Obj {
@@ -87,10 +94,14 @@ impl std::fmt::Debug for Obj {
             .field("name", &self.name)
             .field("parent", &self.parent)
             .field("visible", &self.visible)
+            .field("active", &self.active)
             .field("subspace", &self.subspace)
             .field("triangle_mesh", &self.triangle_mesh)
+            .field("mesh_path", &self.mesh_path)
+            .field("material_path", &self.material_path)
             .field("script", &self.script)
-            .field("children", &self.children).finish()
+            .field("children", &self.children)
+            .field("generated", &self.generated).finish()
     }
 }
 
@@ -102,13 +113,14 @@ impl Clone for Obj {
             name: self.name.clone(),
             parent: self.parent.clone(),
             visible: self.visible,
-            subspace: self.subspace,
+            active: self.active,
+            subspace: self.subspace.clone(),
             triangle_mesh: self.triangle_mesh.clone(),
+            mesh_path: self.mesh_path.clone(),
+            material_path: self.material_path.clone(),
             script: self.script.clone(),
-            children: self.children.clone().into_iter().map(|_child| {
-                // Dangerous
-                todo!();
-            }).collect::<Vec<Arc<RwLock<dyn GameObject>>>>(),
+            children: Vec::new(), // see game_object::instantiate for copying children
+            generated: self.generated,
         }
     }
 }
@@ -148,17 +160,22 @@ impl Obj {
                 None => {
                     ParentWrapper::Scene(engine_globals.scene)
                 }
-            },
+            }.downgrade(),
 
             visible,
 
-            subspace: Space::new(position, rotation, scale_factor),
+            subspace: Transform::new(Space::new(position, rotation, scale_factor)),
+            active: true,
 
             triangle_mesh: Vec::new(),
+            mesh_path: None,
+            material_path: None,
 
             script,
 
-            children: Vec::new()
+            children: Vec::new(),
+
+            generated: false,
         }));
     }
 
@@ -189,17 +206,22 @@ impl Obj {
                 None => {
                     ParentWrapper::Scene(engine_globals.scene)
                 }
-            },
+            }.downgrade(),
 
             visible,
 
-            subspace: Space::new(position, rotation, scale_factor),
+            subspace: Transform::new(Space::new(position, rotation, scale_factor)),
+            active: true,
 
             triangle_mesh: triangle_mesh_vec,
+            mesh_path: None,
+            material_path: None,
 
             script,
 
             children: Vec::new(),
+
+            generated: false,
         }));
     }
     
@@ -331,6 +353,7 @@ impl Obj {
             engine_globals.clone(), 
             script
         );
+        this.write().unwrap().mesh_path = Some(path.to_string());
 
         //   Group Data   //
 
@@ -412,6 +435,7 @@ impl Obj {
                                 engine_globals.clone(),
                                 None
                             );
+                            new_group.write().unwrap().generated = true;
                             wlock_current_group.add_child(new_group.clone());
                             drop(wlock_current_group);
                             current_group = Some(new_group);
@@ -432,6 +456,7 @@ impl Obj {
                         engine_globals.clone(),
                         None
                     );
+                    new_object.write().unwrap().generated = true;
 
                     current_group.clone().unwrap().write().unwrap().add_child(new_object.clone());
                     current_group = Some(new_object);
@@ -458,6 +483,80 @@ impl Obj {
 
         Ok(this)
     }
+
+    /// Replaces the material of every triangle mesh with a material from an mtl file.
+    ///
+    /// Children that were created while loading the obj file are given the material as well.
+    /// # Arguments
+    /// * `library` - The path to the mtl file.
+    /// * `material` - The name of the material within the mtl file.
+    /// * `queue` - The graphics queue.
+    pub fn set_material(&mut self, library: &str, material: &str, queue: Arc<Queue>) -> Result<(), &'static str> {
+        let (new_material, future) = Material::from_mtllib(library, queue.clone()).remove(material)
+            .ok_or("The material does not exist within the library.")?;
+        if future.queue().is_some() {
+            let _ = future.then_signal_fence_and_flush().unwrap().wait(None);
+        }
+        let material_set = new_material.into_set(queue.clone());
+
+        self.triangle_mesh = self.triangle_mesh.iter().map(|triangle_mesh| {
+            let mut triangle_mesh = (**triangle_mesh).clone();
+            triangle_mesh.material = Some(material_set.clone());
+            Arc::new(triangle_mesh)
+        }).collect();
+        self.material_path = Some((library.to_string(), material.to_string()));
+
+        self.children.clone().into_iter().try_for_each(|child| {
+            let mut child = child.write().unwrap();
+            match child.as_any_mut().downcast_mut::<Obj>() {
+                Some(obj) if obj.generated => obj.set_material(library, material, queue.clone()),
+                _ => Ok(())
+            }
+        })
+    }
+
+    /// Shows a texture as the diffuse color (map_Kd) of every triangle mesh. e.g. the texture of a camera
+    /// rendering to a texture. See `graphics::render_target`.
+    ///
+    /// The texture is tinted by the diffuse color of each material. Triangle meshes without a material are
+    /// given a white one. Children that were created while loading the obj file are given the texture as well.
+    /// # Arguments
+    /// * `texture` - The texture.
+    /// * `queue` - The graphics queue.
+    pub fn set_diffuse_map(&mut self, texture: Arc<Texture>, queue: Arc<Queue>) {
+        let plain_set = Material::from_texture(texture.clone()).into_set(queue.clone());
+
+        self.triangle_mesh = self.triangle_mesh.iter().map(|triangle_mesh| {
+            let mut triangle_mesh = (**triangle_mesh).clone();
+            triangle_mesh.material = Some(match triangle_mesh.material.take() {
+                Some((mut material, mut maps)) => {
+                    material.diffuse[3] = 1.0; // has a map. see Material::into_set
+                    maps[0] = texture.clone();
+                    (material, maps)
+                },
+                None => plain_set.clone(),
+            });
+            Arc::new(triangle_mesh)
+        }).collect();
+
+        self.children.clone().into_iter().for_each(|child| {
+            let mut child = child.write().unwrap();
+            if let Some(obj) = child.as_any_mut().downcast_mut::<Obj>() {
+                if obj.generated {
+                    obj.set_diffuse_map(texture.clone(), queue.clone());
+                }
+            }
+        });
+    }
+
+    /// \[backend\] Describes the Obj for saving. See `scene::serialization`.
+    pub(crate) fn describe(&self) -> GameObjectKind {
+        GameObjectKind::Obj {
+            visible: self.visible,
+            mesh: self.mesh_path.clone(),
+            material: self.material_path.clone().map(|(library, name)| MaterialDescriptor { library, name }),
+        }
+    }
     
     // pub fn from_obj_old<'a>( // couple mistakes I caught are still in here
     //         name: Option<&str>, 
@@ -0,0 +1,160 @@
+//! A pool of reusable copies of a GameObject.
+//!
+//! Spawning a GameObject for every projectile means loading or copying it each time. A pool creates
+//! the copies once and hands them out again once they are released. Released copies keep their IDs
+//! and are only removed from their parent, so their meshes and buffers are never reallocated.
+//!
+//! ## Example
+//! ```no_run
+//! # use feo_oop_engine::{scene::game_object::{obj::Obj, pool::ObjectPool}, registration::relation::ParentWrapper, scripting::swap::Swap};
+//! # fn f(pew: std::sync::Arc<std::sync::RwLock<Obj>>, scene: std::sync::Arc<std::sync::RwLock<feo_oop_engine::scene::Scene>>) {
+//! let pool = ObjectPool::new(&pew, ParentWrapper::Scene(scene), 32);
+//!
+//! // when firing
+//! let swap = Swap::Spawn(pool.acquire().unwrap());
+//!
+//! // instead of Swap::Delete
+//! # let pew = pool.acquire().unwrap();
+//! let swap = pool.release(&pew);
+//! # }
+//! ```
+//!
+use {
+    super::{GameObject, instantiate},
+    crate::{
+        registration::{
+            relation::{ParentWrapper, WeakParentWrapper},
+            id::ID,
+        },
+        scripting::swap::Swap,
+    },
+    feo_math::utils::space::Space,
+    std::sync::{Arc, Mutex, RwLock},
+};
+
+/// A pool of copies of a template GameObject.
+///
+/// Cloning an ObjectPool only creates another handle to the same pool so that it can be stored in the globals of scripts.
+pub struct ObjectPool<T> where T: GameObject + Clone {
+    inner: Arc<Mutex<ObjectPoolInner<T>>>,
+}
+
+struct ObjectPoolInner<T> where T: GameObject + Clone {
+    template: Arc<RwLock<T>>,
+    template_subspace: Space,
+    parent: WeakParentWrapper, // weak as the pool is usually kept in the globals of a script within the scene
+    free: Vec<Arc<RwLock<T>>>,
+    in_use: Vec<(ID, Arc<RwLock<T>>)>,
+}
+
+impl<T> ObjectPool<T> where T: GameObject + Clone {
+    /// Creates a pool with a number of copies of a template.
+    ///
+    /// The template itself is never handed out so it does not need to be within the scene.
+    /// # Arguments
+    /// * `template` - The GameObject to copy. See `game_object::instantiate`.
+    /// * `parent` - The parent of the copies.
+    /// * `size` - The number of copies to create up front.
+    pub fn new(template: &Arc<RwLock<T>>, parent: ParentWrapper, size: usize) -> Self {
+        let template_subspace = template.read().unwrap().get_transform().get_local();
+        let free = (0..size).map(|_| instantiate(template, parent.clone())).collect();
+        ObjectPool {
+            inner: Arc::new(Mutex::new(ObjectPoolInner {
+                template: template.clone(),
+                template_subspace,
+                parent: parent.downgrade(),
+                free,
+                in_use: Vec::new(),
+            }))
+        }
+    }
+
+    /// Takes a copy out of the pool. A new copy is created if every copy is in use.
+    /// Returns None if the parent of the copies no longer exists.
+    ///
+    /// The copy is given the transform of the template and its scripts are restarted.
+    /// It is not added to its parent. Return `Swap::Spawn` with it from a script to do so.
+    pub fn acquire(&self) -> Option<Arc<RwLock<T>>> {
+        let mut inner = self.inner.lock().unwrap();
+        let parent = inner.parent.upgrade()?;
+        let game_object = match inner.free.pop() {
+            Some(game_object) => game_object,
+            None => instantiate(&inner.template, parent),
+        };
+
+        {
+            let mut write = game_object.write().unwrap();
+            write.set_subspace(inner.template_subspace);
+            write.restart_script();
+            if let Ok(globals) = inner.template.read().unwrap().get_globals() {
+                let _ = write.set_globals(globals);
+            }
+        }
+
+        let id = game_object.read().unwrap().get_id();
+        inner.in_use.push((id, game_object.clone()));
+        Some(game_object)
+    }
+
+    /// Puts a copy back into the pool.
+    ///
+    /// Returns the swap that removes it from its parent. Return it from a script instead of `Swap::Delete`.
+    /// The copy can only be acquired again once the swap has been applied at the end of the frame.
+    /// Copies that do not belong to the pool are deleted instead.
+    /// # Arguments
+    /// * `game_object` - The copy.
+    pub fn release(&self, game_object: &Arc<RwLock<T>>) -> Swap {
+        let id = game_object.read().unwrap().get_id();
+        let mut inner = self.inner.lock().unwrap();
+        match inner.in_use.iter().position(|(in_use, _)| *in_use == id) {
+            Some(i) => {
+                let (_, game_object) = inner.in_use.swap_remove(i);
+                let pool = self.inner.clone();
+                Swap::Release(id, Box::new(move || pool.lock().unwrap().free.push(game_object)))
+            },
+            None => Swap::Delete(id),
+        }
+    }
+
+    /// Puts a copy back into the pool by its ID. See `release`.
+    /// # Arguments
+    /// * `id` - The ID of the copy.
+    pub fn release_id(&self, id: &ID) -> Swap {
+        let game_object = self.inner.lock().unwrap().in_use.iter()
+            .find(|(in_use, _)| in_use == id)
+            .map(|(_, game_object)| game_object.clone());
+        match game_object {
+            Some(game_object) => self.release(&game_object),
+            None => Swap::Delete(id.clone()),
+        }
+    }
+
+    /// Gets the number of copies that are ready to be acquired.
+    pub fn available(&self) -> usize {
+        self.inner.lock().unwrap().free.len()
+    }
+
+    /// Gets the number of copies that have been acquired and not released.
+    pub fn in_use(&self) -> usize {
+        self.inner.lock().unwrap().in_use.len()
+    }
+}
+
+impl<T> Clone for ObjectPool<T> where T: GameObject + Clone {
+    fn clone(&self) -> Self {
+        ObjectPool {
+            inner: self.inner.clone()
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for ObjectPool<T> where T: GameObject + Clone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.lock().unwrap();
+        let template = inner.template.read().unwrap().get_id();
+        f.debug_struct("ObjectPool")
+            .field("template", &template)
+            .field("available", &inner.free.len())
+            .field("in_use", &inner.in_use.len()).finish()
+    }
+}
@@ -0,0 +1,162 @@
+//! Lifetimes after which GameObjects are deleted.
+//!
+//! Any GameObject in a scene can be given a lifetime in seconds or frames. Once it runs out the optional
+//! on_expire function is run and the GameObject is deleted using `Swap::Delete`, so it receives a
+//! `Despawning` event like any other deleted GameObject. The lifetimes are counted down once per frame
+//! before the frame scripts are run.
+//!
+//! ## Example
+//! ```no_run
+//! # #[macro_use] extern crate feo_oop_engine;
+//! # use feo_oop_engine::{scene::{game_object::GameObject, lifetime::Lifetime}, scripting::{BoxedExpireFn, globals::EngineGlobals, swap::Swap}};
+//! # use std::sync::{Arc, RwLock};
+//! on_expire! {
+//!     async fn explode<'r>(this: Arc<RwLock<dyn GameObject>>, _: EngineGlobals) -> Swap {
+//!         println!("{} expired", this.read().unwrap().get_name());
+//!         Swap::None
+//!     }
+//! }
+//!
+//! # fn f(bullet: Arc<RwLock<dyn GameObject>>, engine_globals: EngineGlobals) {
+//! let id = bullet.read().unwrap().get_id();
+//! let on_expire: BoxedExpireFn = Box::pin(explode);
+//! engine_globals.scene.read().unwrap().set_lifetime(id, Lifetime::Seconds(5.0), Some(on_expire));
+//! # }
+//! ```
+//!
+use {
+    super::Scene,
+    crate::{
+        registration::id::ID,
+        scripting::{
+            BoxedExpireFn,
+            executor::Spawner,
+            swap::Swap,
+        },
+    },
+    std::{
+        collections::HashMap,
+        sync::Mutex,
+        time::Instant,
+    },
+};
+
+/// How long a GameObject exists for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lifetime {
+    Seconds(f32),
+    Frames(u64),
+}
+
+impl Lifetime {
+    fn is_expired(&self) -> bool {
+        match self {
+            Lifetime::Seconds(seconds) => *seconds <= 0.0,
+            Lifetime::Frames(frames) => *frames == 0,
+        }
+    }
+}
+
+struct LifetimeEntry {
+    remaining: Lifetime,
+    on_expire: Option<BoxedExpireFn>,
+}
+
+/// \[backend\] The lifetimes of the GameObjects within a scene.
+#[derive(Default)]
+pub(crate) struct Lifetimes {
+    entries: Mutex<HashMap<ID, LifetimeEntry>>,
+    last_tick: Mutex<Option<Instant>>,
+}
+
+impl Lifetimes {
+    /// Counts down every lifetime and deletes the GameObjects whose lifetimes ran out.
+    pub(crate) fn tick(&self, scene: &Scene, spawner: Spawner) {
+        let now = Instant::now();
+        let delta = self.last_tick.lock().unwrap()
+            .replace(now)
+            .map_or(0.0, |last_tick| (now - last_tick).as_secs_f32());
+
+        let mut expired = Vec::new();
+        self.entries.lock().unwrap().retain(|id, entry| {
+            if !id.is_alive() {
+                return false;
+            }
+            entry.remaining = match entry.remaining {
+                Lifetime::Seconds(seconds) => Lifetime::Seconds(seconds - delta),
+                Lifetime::Frames(frames) => Lifetime::Frames(frames.saturating_sub(1)),
+            };
+            if entry.remaining.is_expired() {
+                expired.push((id.clone(), entry.on_expire.take()));
+                false
+            } else {
+                true
+            }
+        });
+
+        expired.into_iter().for_each(|(id, on_expire)| {
+            if let Some(game_object) = scene.find_by_id(&id) {
+                if let Some(on_expire) = on_expire {
+                    let engine_globals = spawner.engine_globals.clone();
+                    spawner.spawn(async move {
+                        (*on_expire)(game_object, engine_globals).await
+                    });
+                }
+                spawner.spawn(async move { Swap::Delete(id) });
+            }
+        });
+    }
+
+    /// Restarts the clock so the time a scene spent inactive is not counted.
+    pub(crate) fn reset_clock(&self) {
+        *self.last_tick.lock().unwrap() = None;
+    }
+}
+
+impl Clone for Lifetimes {
+    fn clone(&self) -> Self {
+        Lifetimes {
+            entries: Mutex::new(self.entries.lock().unwrap().iter().map(|(id, entry)| {
+                (id.clone(), LifetimeEntry { remaining: entry.remaining, on_expire: entry.on_expire.clone() })
+            }).collect()),
+            last_tick: Mutex::new(*self.last_tick.lock().unwrap()),
+        }
+    }
+}
+
+impl std::fmt::Debug for Lifetimes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lifetimes")
+            .field("len", &self.entries.lock().unwrap().len()).finish()
+    }
+}
+
+impl Scene {
+    /// Gives a GameObject a lifetime after which it is deleted. Replaces any lifetime it already has.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    /// * `lifetime` - How long the GameObject exists for from now.
+    /// * `on_expire` - An optional function run before the GameObject is deleted. See the `on_expire!` macro.
+    pub fn set_lifetime(&self, id: ID, lifetime: Lifetime, on_expire: Option<BoxedExpireFn>) {
+        self.lifetimes.entries.lock().unwrap().insert(id, LifetimeEntry { remaining: lifetime, on_expire });
+    }
+
+    /// Removes the lifetime of a GameObject so that it is not deleted.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn clear_lifetime(&self, id: &ID) {
+        self.lifetimes.entries.lock().unwrap().remove(id);
+    }
+
+    /// Gets the remaining lifetime of a GameObject.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn get_lifetime(&self, id: &ID) -> Option<Lifetime> {
+        self.lifetimes.entries.lock().unwrap().get(id).map(|entry| entry.remaining)
+    }
+
+    /// \[backend\] Counts down the lifetimes of the GameObjects. Run once per frame.
+    pub fn tick_lifetimes(&self, spawner: Spawner) {
+        self.lifetimes.tick(self, spawner);
+    }
+}
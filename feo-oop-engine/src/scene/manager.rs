@@ -0,0 +1,233 @@
+//! Holds the scenes of a game and switches between them.
+//!
+//! Only the active scene is run and rendered. Scripts request a switch or an additive load through
+//! the SceneManager in the engine globals. The requests are applied in order at the end of the frame
+//! once every script has finished.
+//!
+//! When a scene is switched away from, every GameObject within it receives a `Despawning` event and
+//! its script is reset so that its start function runs again if the scene is switched back to.
+//!
+//! ## Example
+//! ```no_run
+//! # fn f(engine_globals: feo_oop_engine::scripting::globals::EngineGlobals) {
+//! let mut scene_manager = engine_globals.scene_manager.write().unwrap();
+//! scene_manager.switch_to("level_1").unwrap();
+//! scene_manager.load_additive("hud").unwrap();
+//! # }
+//! ```
+//!
+use {
+    super::{
+        Scene,
+        game_object::{GameObject, instantiate_dyn},
+        serialization::{SceneDescriptor, SceneFileError},
+    },
+    crate::{
+        registration::relation::{Parent, ParentWrapper},
+        scripting::{
+            globals::EngineGlobals,
+            new_executor_and_spawner,
+        },
+        event::UserEvent,
+    },
+    std::{
+        collections::HashMap,
+        path::Path,
+        sync::{Arc, RwLock},
+    },
+    futures::executor::block_on,
+};
+
+/// A change to the active scene waiting for the end of the frame.
+#[derive(Debug)]
+enum SceneRequest {
+    Switch(String),
+    Additive(String),
+    Add(Vec<Arc<RwLock<dyn GameObject>>>),
+}
+
+/// Holds named scenes and keeps track of the active one.
+#[derive(Debug)]
+pub struct SceneManager {
+    scenes: HashMap<String, Arc<RwLock<Scene>>>,
+    active: String,
+    requests: Vec<SceneRequest>,
+}
+
+impl SceneManager {
+    /// The name the scene the engine is initialized with is registered under.
+    pub const DEFAULT_SCENE: &'static str = "main";
+
+    /// Creates a new SceneManager with an active scene.
+    /// # Arguments
+    /// * `name` - The name of the active scene.
+    /// * `scene` - The active scene.
+    pub fn new(name: &str, scene: Arc<RwLock<Scene>>) -> Self {
+        let mut scenes = HashMap::new();
+        scenes.insert(name.to_string(), scene);
+        SceneManager {
+            scenes,
+            active: name.to_string(),
+            requests: Vec::new(),
+        }
+    }
+
+    /// Registers a scene under a name. A scene that is already registered under the name is replaced unless it is active.
+    ///
+    /// Note that GameObjects must be created with engine globals whose scene is this scene to be parented to it.
+    /// # Arguments
+    /// * `name` - The name of the scene.
+    /// * `scene` - The scene.
+    pub fn add_scene(&mut self, name: &str, scene: Arc<RwLock<Scene>>) -> Result<(), &'static str> {
+        if name == self.active {
+            return Err("The active scene cannot be replaced.");
+        }
+        self.scenes.insert(name.to_string(), scene);
+        Ok(())
+    }
+
+    /// Replaces the active scene with another, e.g. one loaded with `Scene::load`, and returns the replaced scene.
+    /// The engine runs the active scene of its SceneManager.
+    ///
+    /// Use it before the engine runs. A running engine should `add_scene` and `switch_to` it instead
+    /// so that the GameObjects of the old scene are despawned.
+    /// # Arguments
+    /// * `scene` - The new active scene.
+    pub fn replace_active(&mut self, scene: Arc<RwLock<Scene>>) -> Arc<RwLock<Scene>> {
+        self.scenes.insert(self.active.clone(), scene).expect("the active scene is always registered")
+    }
+
+    /// Unregisters a scene.
+    /// # Arguments
+    /// * `name` - The name of the scene.
+    pub fn remove_scene(&mut self, name: &str) -> Result<Arc<RwLock<Scene>>, &'static str> {
+        if name == self.active {
+            return Err("The active scene cannot be removed.");
+        }
+        self.scenes.remove(name).ok_or("No scene is registered under that name.")
+    }
+
+    /// Gets a registered scene.
+    /// # Arguments
+    /// * `name` - The name of the scene.
+    pub fn get_scene(&self, name: &str) -> Option<Arc<RwLock<Scene>>> {
+        self.scenes.get(name).cloned()
+    }
+
+    /// Gets the name of the active scene.
+    pub fn get_active_name(&self) -> &str {
+        &self.active
+    }
+
+    /// Gets the active scene.
+    pub fn get_active(&self) -> Arc<RwLock<Scene>> {
+        self.scenes[&self.active].clone()
+    }
+
+    /// Switches the active scene at the end of the frame.
+    ///
+    /// The scene must have a main camera.
+    /// # Arguments
+    /// * `name` - The name of the scene to switch to.
+    pub fn switch_to(&mut self, name: &str) -> Result<(), &'static str> {
+        if !self.scenes.contains_key(name) {
+            return Err("No scene is registered under that name.");
+        }
+        self.requests.push(SceneRequest::Switch(name.to_string()));
+        Ok(())
+    }
+
+    /// Copies the GameObjects of a registered scene into the active scene at the end of the frame.
+    /// The registered scene is left unchanged so it can be loaded again.
+    /// # Arguments
+    /// * `name` - The name of the scene to copy from.
+    pub fn load_additive(&mut self, name: &str) -> Result<(), &'static str> {
+        if !self.scenes.contains_key(name) {
+            return Err("No scene is registered under that name.");
+        }
+        self.requests.push(SceneRequest::Additive(name.to_string()));
+        Ok(())
+    }
+
+    /// Loads the GameObjects of a level file. They are added to the active scene at the end of the frame.
+    /// See `scene::serialization`.
+    /// # Arguments
+    /// * `path` - The path of the level file.
+    /// * `engine_globals` - The engine globals. Scripts are looked up in its script registry.
+    pub fn load_additive_file<P: AsRef<Path>>(&mut self, path: P, engine_globals: &EngineGlobals) -> Result<(), SceneFileError> {
+        let descriptor = SceneDescriptor::read(path.as_ref())?;
+        let game_objects = descriptor.children.iter()
+            .map(|child| child.build(None, engine_globals))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.requests.push(SceneRequest::Add(game_objects));
+        Ok(())
+    }
+
+    /// \[backend\] Applies the requested changes and updates the scene of the engine globals.
+    ///
+    /// Must not be called while a script is running.
+    pub(crate) fn apply_requests(scene_manager: &Arc<RwLock<SceneManager>>, engine_globals: &mut EngineGlobals) {
+        loop {
+            // the lock is released before any script runs
+            let (request, scene) = {
+                let mut scene_manager = scene_manager.write().unwrap();
+                if scene_manager.requests.is_empty() {
+                    break;
+                }
+                let request = scene_manager.requests.remove(0);
+                let scene = match &request {
+                    SceneRequest::Switch(name) | SceneRequest::Additive(name) => scene_manager.scenes.get(name).cloned(),
+                    SceneRequest::Add(_) => None,
+                };
+                (request, scene)
+            };
+
+            match request {
+                SceneRequest::Switch(name) => {
+                    let new_scene = match scene {
+                        Some(new_scene) => new_scene,
+                        None => continue, // removed since the request
+                    };
+                    if Arc::ptr_eq(&new_scene, &engine_globals.scene) {
+                        continue;
+                    }
+
+                    // destroy the old scene
+                    let old_scene = engine_globals.scene.clone();
+                    let (executor, _) = new_executor_and_spawner(engine_globals.clone());
+                    executor.unload(old_scene.clone());
+                    old_scene.read().unwrap().get_children().into_iter().for_each(|child| child.write().unwrap().restart_script());
+
+                    scene_manager.write().unwrap().active = name;
+                    new_scene.read().unwrap().lifetimes.reset_clock();
+                    new_scene.read().unwrap().physics.reset_clock();
+                    engine_globals.scene = new_scene;
+                },
+                SceneRequest::Additive(_) => {
+                    let template = match scene {
+                        Some(template) => template,
+                        None => continue,
+                    };
+                    let game_objects = template.read().unwrap().get_children().iter()
+                        .map(|child| instantiate_dyn(child, ParentWrapper::Scene(engine_globals.scene.clone())))
+                        .collect();
+                    Self::add(game_objects, engine_globals);
+                },
+                SceneRequest::Add(game_objects) => {
+                    Self::add(game_objects, engine_globals);
+                }
+            }
+        }
+    }
+
+    fn add(game_objects: Vec<Arc<RwLock<dyn GameObject>>>, engine_globals: &EngineGlobals) {
+        let proxy = block_on(engine_globals.event_loop_proxy.lock());
+        game_objects.into_iter().for_each(|game_object| {
+            unsafe { game_object.write().unwrap().set_parent(ParentWrapper::Scene(engine_globals.scene.clone())); }
+            let id = game_object.read().unwrap().get_id();
+            engine_globals.scene.write().unwrap().add_child(game_object);
+            proxy.send_event(UserEvent::ChildAdded(None, id.clone())).unwrap();
+            proxy.send_event(UserEvent::Spawned(id)).unwrap();
+        });
+    }
+}
@@ -3,15 +3,25 @@
 //! TODO
 //! 
 pub mod game_object;
+pub mod query;
+pub mod manager;
+pub mod debug;
+pub mod lifetime;
+pub mod spatial;
+pub mod serialization;
 
 use std::any::Any;
 
 use vulkano::{swapchain::SwapchainAcquireFuture, sync::GpuFuture};
 use winit::window::Window;
 
-use crate::graphics::{draw_pass_manager::DrawPassManager, frame_system::FrameSystem, lighting_pass_manager::LightingPassManager};
+use crate::graphics::{draw_pass_manager::DrawPassManager, frame_system::FrameSystem, lighting_pass_manager::LightingPassManager, render_target::RenderTargets};
 
 use game_object::camera::Camera;
+use query::SceneIndex;
+use lifetime::Lifetimes;
+use spatial::SpatialIndex;
+use crate::physics::PhysicsWorld;
 
 use {
     crate::{
@@ -24,7 +34,8 @@ use {
         },
         scripting::executor::Spawner,
         components::{
-            triangle_mesh::TriangleMesh 
+            triangle_mesh::TriangleMesh,
+            transform::{Transform, WorldTransform},
         },
         event::UserEvent,
         graphics::{
@@ -43,11 +54,17 @@ use {
 };
 
 /// A scene in which GameObjects can exist.
-#[derive(Clone, Parent, Drawable, Debug)]
+#[derive(Clone, Drawable, Debug)]
 pub struct Scene {
-    pub worldspace: Space,
+    pub worldspace: Transform,
     pub children: Vec<Arc<RwLock<dyn GameObject>>>,
     pub main_camera: Option<Arc<RwLock<dyn Camera>>>,
+
+    pub(crate) index: SceneIndex,
+    pub(crate) lifetimes: Lifetimes,
+    pub(crate) spatial: SpatialIndex,
+    pub(crate) physics: PhysicsWorld,
+    pub(crate) render_targets: RenderTargets,
 }
 
 impl Scene {
@@ -63,9 +80,14 @@ impl Scene {
     /// ```
     pub fn new(worldspace: Option<Space>) -> Arc<RwLock<Self>>{
         Arc::new(RwLock::new(Scene{
-            worldspace: worldspace.unwrap_or_else(|| Space::new(None, None, None)),
+            worldspace: Transform::new(worldspace.unwrap_or_else(|| Space::new(None, None, None))),
             children: Vec::new(),
             main_camera: None,
+            index: SceneIndex::default(),
+            lifetimes: Lifetimes::default(),
+            spatial: SpatialIndex::default(),
+            physics: PhysicsWorld::default(),
+            render_targets: RenderTargets::default(),
         }))
     }
     
@@ -103,7 +125,7 @@ impl Scene {
         // write_lock.spawn_script_handler(main_camera_gameobject, spawner, event);
     }
 
-    /// \[backend\] Renders the scene.
+    /// \[backend\] Renders the scene. The cameras rendering into textures go first. See `graphics::render_target`.
     #[inline]
     pub fn render(&self,
             this: Arc<RwLock<Scene>>,
@@ -112,21 +134,49 @@ impl Scene {
             acquire_future: SwapchainAcquireFuture<Window>, 
             previous_frame_end: &mut Option<Box<dyn GpuFuture>> ) -> Box<dyn GpuFuture> {
         
+        let future = Box::new(previous_frame_end.take().unwrap().join(acquire_future));
+        let future = self.render_to_textures(this.clone(), future);
+
+        let main_camera = self.main_camera.clone().expect("No camera defined");
+        self.render_camera(this, main_camera, frame_system, image_num, future)
+    }
+
+    /// \[backend\] Renders the scene as seen by a camera into an image of a frame system after the future.
+    pub(crate) fn render_camera(&self,
+            this: Arc<RwLock<Scene>>,
+            camera: Arc<RwLock<dyn Camera>>,
+            frame_system: &mut FrameSystem,
+            image_num: usize,
+            future: Box<dyn GpuFuture>) -> Box<dyn GpuFuture> {
+
+        // the camera follows the size of the image unless it is letterboxed
+        camera.write().unwrap().resize(frame_system.get_dimensions());
+        let (width, height) = {
+            let camera = camera.read().unwrap();
+            frame_system.letterbox(camera.is_aspect_ratio_fixed().then(|| camera.get_aspect_ratio()))
+        };
+
         frame_system.draw_pass_manager.clear();
         frame_system.lighting_pass_manager.clear();
 
         self.load_into_managers(ParentWrapper::Scene(this), &mut frame_system.draw_pass_manager, &mut frame_system.lighting_pass_manager);
 
-        let main_camera = self.main_camera.clone().expect("No camera defined");
-        frame_system.draw_pass_manager.recreate_camera_set(main_camera.clone());
+        frame_system.draw_pass_manager.recreate_camera_set(camera.clone());
 
-        let main_camera_read = main_camera.read().unwrap();
-        let future = previous_frame_end.take().unwrap().join(acquire_future);
+        // the lighting pass covers the whole image so it sees the view shrunk into the letterbox
+        let letterbox = Matrix4::new(
+            [width,    0.0, 0.0, 0.0],
+            [  0.0, height, 0.0, 0.0],
+            [  0.0,    0.0, 1.0, 0.0],
+            [  0.0,    0.0, 0.0, 1.0]
+        );
+
+        let camera_read = camera.read().unwrap();
         let mut builder = frame_system.pass_builder(
             future, 
             image_num,
-            main_camera_read.build_projection().inverse().transpose(),
-            main_camera_read.get_inversed_subspace()
+            (letterbox * camera_read.build_projection()).inverse().transpose(),
+            camera_read.get_inversed_subspace()
         );
 
         builder.build()
@@ -136,6 +186,47 @@ impl Scene {
     pub fn build_space(&mut self) -> Matrix4<f32>{
         self.worldspace.build()
     }
+
+    /// Gets the cached world transform of the scene's worldspace.
+    pub fn get_world_transform(&self) -> WorldTransform {
+        self.worldspace.resolve(&WorldTransform::identity())
+    }
+}
+
+impl Parent for Scene {
+    fn get_children(&self) -> Vec<Arc<RwLock<dyn GameObject>>> { self.children.clone() }
+
+    fn add_child(&mut self, child: Arc<RwLock<dyn GameObject>>) {
+        self.index.insert_tree(&child);
+        self.children.push(child);
+    }
+
+    unsafe fn remove_child(&mut self, child: Arc<RwLock<dyn GameObject>>) -> Result<(), ()> {
+        let id = child.read().unwrap().get_id();
+        let index = self.children.iter().position(|r| r.read().unwrap().get_id() == id);
+        match index {
+            Some(i) => {
+                self.index.remove_tree(&child);
+                self.children.remove(i);
+                Ok(())
+            },
+            None => Err(())
+        }
+    }
+
+    unsafe fn replace_child(&mut self, old: Arc<RwLock<dyn GameObject>>, new: Arc<RwLock<dyn GameObject>>) -> Result<(), ()> {
+        let id = old.read().unwrap().get_id();
+        let index = self.children.iter().position(|r| r.read().unwrap().get_id() == id);
+        match index {
+            Some(i) => {
+                self.index.remove_tree(&old);
+                self.index.insert_tree(&new);
+                let _ = mem::replace(&mut self.children[i], new);
+                Ok(())
+            },
+            None => Err(())
+        }
+    }
 }
 
 impl Named for Scene {
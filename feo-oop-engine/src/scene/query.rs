@@ -0,0 +1,206 @@
+//! Lookups over the GameObjects that exist within a scene.
+//!
+//! Lookups by ID go through a cached index kept by the scene so they do not have to
+//! search the whole hierarchy. Every other lookup walks the hierarchy depth first.
+//!
+//! Note that a lookup that has to walk the hierarchy takes a read lock on every
+//! GameObject it passes and a lookup by ID takes one on the GameObject and its parents.
+//! Do not call these while holding a write lock on a GameObject within the scene.
+//!
+use {
+    super::{
+        Scene,
+        game_object::{GameObject, GameObjectHandle},
+    },
+    crate::registration::{
+        relation::{Parent, ParentWrapper},
+        id::ID,
+    },
+    std::{
+        collections::HashMap,
+        sync::{Arc, RwLock, Weak}
+    },
+};
+
+/// \[backend\] A cache mapping the IDs of GameObjects to the GameObjects themselves.
+#[derive(Default)]
+pub(crate) struct SceneIndex {
+    objects: RwLock<HashMap<ID, Weak<RwLock<dyn GameObject>>>>,
+}
+
+impl SceneIndex {
+    /// Indexes a GameObject and all of its children.
+    pub(crate) fn insert_tree(&self, game_object: &Arc<RwLock<dyn GameObject>>) {
+        let read = game_object.read().unwrap();
+        self.objects.write().unwrap().insert(read.get_id(), Arc::downgrade(game_object));
+        read.get_children().iter().for_each(|child| self.insert_tree(child));
+    }
+
+    /// Removes a GameObject and all of its children from the index.
+    pub(crate) fn remove_tree(&self, game_object: &Arc<RwLock<dyn GameObject>>) {
+        let read = game_object.read().unwrap();
+        self.objects.write().unwrap().remove(&read.get_id());
+        read.get_children().iter().for_each(|child| self.remove_tree(child));
+    }
+
+    /// Gets an indexed GameObject if it is still alive.
+    pub(crate) fn get(&self, id: &ID) -> Option<Arc<RwLock<dyn GameObject>>> {
+        self.objects.read().unwrap().get(id).and_then(Weak::upgrade)
+    }
+
+    /// Removes a single GameObject from the index.
+    pub(crate) fn remove(&self, id: &ID) {
+        self.objects.write().unwrap().remove(id);
+    }
+
+    /// Indexes a single GameObject.
+    pub(crate) fn insert(&self, id: ID, game_object: &Arc<RwLock<dyn GameObject>>) {
+        self.objects.write().unwrap().insert(id, Arc::downgrade(game_object));
+    }
+}
+
+impl Clone for SceneIndex {
+    fn clone(&self) -> Self {
+        SceneIndex {
+            objects: RwLock::new(self.objects.read().unwrap().clone())
+        }
+    }
+}
+
+impl std::fmt::Debug for SceneIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SceneIndex")
+            .field("len", &self.objects.read().unwrap().len()).finish()
+    }
+}
+
+/// A depth first iterator over a hierarchy of GameObjects.
+pub struct Descendants {
+    stack: Vec<Arc<RwLock<dyn GameObject>>>,
+}
+
+impl Descendants {
+    /// Creates an iterator over the given GameObjects and all of their descendants.
+    pub fn new(mut roots: Vec<Arc<RwLock<dyn GameObject>>>) -> Self {
+        roots.reverse();
+        Descendants { stack: roots }
+    }
+}
+
+impl Iterator for Descendants {
+    type Item = Arc<RwLock<dyn GameObject>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.stack.pop()?;
+        let mut children = next.read().unwrap().get_children();
+        children.reverse();
+        self.stack.append(&mut children);
+        Some(next)
+    }
+}
+
+impl Scene {
+    /// Returns an iterator over every GameObject in the scene, parents before their children.
+    pub fn descendants(&self) -> Descendants {
+        Descendants::new(self.get_children())
+    }
+
+    /// Finds the GameObject with the given ID.
+    ///
+    /// GameObjects that were added through the scene or the swap system are found in the index. Their
+    /// parents are checked to still lead to the scene so the cost grows with their depth and not the size of the scene.
+    /// Other GameObjects are found by walking the hierarchy once after which they are indexed.
+    /// Stale IDs and GameObjects that were removed from the scene always return None.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn find_by_id(&self, id: &ID) -> Option<Arc<RwLock<dyn GameObject>>> {
+        if !id.is_alive() {
+            return None;
+        }
+
+        if let Some(game_object) = self.index.get(id) {
+            if self.contains(&game_object) {
+                return Some(game_object);
+            }
+            // removed with remove_child outside of the swap system
+            self.index.remove(id);
+        }
+
+        let found = self.query(|game_object| game_object.get_id() == *id).next();
+        if let Some(game_object) = found.as_ref() {
+            self.index.insert(id.clone(), game_object);
+        }
+        found
+    }
+
+    /// \[backend\] Checks that the parents of a GameObject still lead to the scene.
+    fn contains(&self, game_object: &Arc<RwLock<dyn GameObject>>) -> bool {
+        let mut current = game_object.clone();
+        loop {
+            let parent = match current.read().unwrap().get_parent() {
+                Some(parent) => parent,
+                None => return false,
+            };
+            match parent {
+                ParentWrapper::GameObject(parent) => {
+                    if !parent.read().unwrap().get_children().iter().any(|child| Arc::ptr_eq(child, &current)) {
+                        return false;
+                    }
+                    current = parent;
+                },
+                ParentWrapper::Scene(_) => return self.children.iter().any(|child| Arc::ptr_eq(child, &current)),
+            }
+        }
+    }
+
+    /// Finds a GameObject by the path of names leading to it.
+    /// # Arguments
+    /// * `path` - The names of the GameObjects leading to the GameObject separated by `/`.
+    /// # Examples
+    /// ```no_run
+    /// # let scene = feo_oop_engine::scene::Scene::new(None);
+    /// let muzzle = scene.read().unwrap().find_path("player/gun/muzzle");
+    /// ```
+    pub fn find_path(&self, path: &str) -> Option<Arc<RwLock<dyn GameObject>>> {
+        let mut names = path.split('/').filter(|name| !name.is_empty());
+
+        let first = names.next()?;
+        let mut current = self.get_children().into_iter()
+            .find(|child| child.read().unwrap().get_name() == first)?;
+
+        for name in names {
+            let next = current.read().unwrap().get_children().into_iter()
+                .find(|child| child.read().unwrap().get_name() == name)?;
+            current = next;
+        }
+
+        Some(current)
+    }
+
+    /// Finds every GameObject of the type `T`.
+    /// # Examples
+    /// ```no_run
+    /// # use feo_oop_engine::scene::game_object::obj::Obj;
+    /// # let scene = feo_oop_engine::scene::Scene::new(None);
+    /// let objs = scene.read().unwrap().find_all::<Obj>();
+    /// ```
+    pub fn find_all<T: GameObject>(&self) -> Vec<Arc<RwLock<T>>> {
+        self.descendants()
+            .filter_map(|game_object| game_object.downcast::<T>())
+            .collect()
+    }
+
+    /// Returns an iterator over every GameObject for which the predicate returns true.
+    /// # Arguments
+    /// * `predicate` - The condition a GameObject must satisfy.
+    /// # Examples
+    /// ```no_run
+    /// # use feo_oop_engine::graphics::Drawable;
+    /// # let scene = feo_oop_engine::scene::Scene::new(None);
+    /// let hidden = scene.read().unwrap().query(|obj| !obj.get_visible()).count();
+    /// ```
+    pub fn query<F>(&self, mut predicate: F) -> impl Iterator<Item = Arc<RwLock<dyn GameObject>>>
+    where F: FnMut(&dyn GameObject) -> bool {
+        self.descendants().filter(move |game_object| predicate(&*game_object.read().unwrap()))
+    }
+}
@@ -0,0 +1,577 @@
+//! Saving and loading scenes from level files.
+//!
+//! A level file describes the hierarchy of a scene. It stores the name, transform and script of every GameObject
+//! along with the properties specific to its type such as the mesh of an Obj or the color of a light.
+//! Files ending in `.json` are written and read as JSON. Every other file is RON.
+//!
+//! Scripts cannot be saved as they are code. They are saved as the name they were registered under
+//! in the `ScriptRegistry` of the engine globals and looked up in the registry when the scene is loaded.
+//! Saving fails for a GameObject whose script was not taken from the registry.
+//! Meshes and materials are saved as the paths of the files they were loaded from.
+//!
+//! The children an Obj creates while loading its obj file are not saved. They are recreated when the obj file is loaded.
+//!
+//! ## Example
+//! ```text
+//! (
+//!     worldspace: (position: (0.0, 0.0, 0.0), rotation: (0.0, 0.0, 0.0, 1.0), scale: (1.0, 1.0, 1.0)),
+//!     children: [
+//!         (
+//!             name: "cube",
+//!             transform: (position: (0.0, 0.0, -5.0)),
+//!             script: Some("spin"),
+//!             kind: Obj(mesh: Some("assets/standard-assets/models/shapes/cube.obj")),
+//!         ),
+//!         (
+//!             name: "camera",
+//!             kind: FpvCamera(main: true, fov: 1, near_plane: 0.1, far_plane: 100.0, aspect_ratio: 1.0),
+//!         ),
+//!     ],
+//! )
+//! ```
+//!
+use {
+    super::{
+        Scene,
+        game_object::{
+            GameObject,
+            GameObjectHandle,
+            obj::Obj,
+            group::Group,
+            camera::{Camera, fpv_camera::FpvCamera, orbit_camera::{OrbitCamera, OrbitTarget}, orthographic_camera::OrthographicCamera},
+            light::{
+                ambient_light::AmbientLight,
+                directional_light::DirectionalLight,
+                point_light::PointLight,
+            },
+        },
+    },
+    crate::{
+        registration::{
+            relation::Parent,
+        },
+        scripting::{
+            Script,
+            globals::EngineGlobals,
+        },
+        components::RGB,
+    },
+    feo_math::{
+        linear_algebra::vector3::Vector3,
+        rotation::quaternion::Quaternion,
+        utils::space::Space,
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        fmt,
+        fs,
+        path::Path,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// Describes a scene.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SceneDescriptor {
+    #[serde(default)]
+    pub worldspace: TransformDescriptor,
+    #[serde(default)]
+    pub children: Vec<GameObjectDescriptor>,
+    /// The main camera if it is not within the hierarchy.
+    #[serde(default)]
+    pub main_camera: Option<GameObjectDescriptor>,
+}
+
+/// Describes a local space.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransformDescriptor {
+    pub position: [f32; 3],
+    pub rotation: [f32; 4], // a quaternion with its real part last
+    pub scale: [f32; 3],
+}
+
+impl Default for TransformDescriptor {
+    fn default() -> Self {
+        TransformDescriptor {
+            position: [0.0, 0.0, 0.0],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl From<Space> for TransformDescriptor {
+    fn from(space: Space) -> Self {
+        TransformDescriptor {
+            position: [space.center.0, space.center.1, space.center.2],
+            rotation: [space.rotation.0, space.rotation.1, space.rotation.2, space.rotation.3],
+            scale: [space.scale_factor.m[0][0], space.scale_factor.m[1][1], space.scale_factor.m[2][2]],
+        }
+    }
+}
+
+impl From<TransformDescriptor> for Space {
+    fn from(transform: TransformDescriptor) -> Self {
+        let [x, y, z] = transform.position;
+        let [a, b, c, r] = transform.rotation;
+        let [sx, sy, sz] = transform.scale;
+        Space::new(Some(Vector3(x, y, z)), Some(Quaternion::new(a, b, c, r)), Some(Vector3(sx, sy, sz)))
+    }
+}
+
+/// Describes a GameObject and its children.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameObjectDescriptor {
+    pub name: String,
+    #[serde(default)]
+    pub transform: TransformDescriptor,
+    /// Whether the GameObject and its children run their scripts and are rendered.
+    #[serde(default = "active")]
+    pub active: bool,
+    /// The name the script is registered under.
+    #[serde(default)]
+    pub script: Option<String>,
+    pub kind: GameObjectKind,
+    #[serde(default)]
+    pub children: Vec<GameObjectDescriptor>,
+}
+
+/// The type of a GameObject and the properties specific to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GameObjectKind {
+    Obj {
+        #[serde(default = "visible")]
+        visible: bool,
+        /// The path to the obj file.
+        #[serde(default)]
+        mesh: Option<String>,
+        /// A material overriding the materials of the obj file.
+        #[serde(default)]
+        material: Option<MaterialDescriptor>,
+    },
+    Group {
+        #[serde(default = "visible")]
+        visible: bool,
+    },
+    FpvCamera {
+        #[serde(default)]
+        main: bool,
+        #[serde(default)]
+        offset: Option<[f32; 3]>,
+        fov: i32,
+        near_plane: f32,
+        far_plane: f32,
+        aspect_ratio: f32,
+        #[serde(default)]
+        fixed_aspect_ratio: bool,
+    },
+    OrthographicCamera {
+        #[serde(default)]
+        main: bool,
+        height: f32,
+        #[serde(default = "zoom")]
+        zoom: f32,
+        near_plane: f32,
+        far_plane: f32,
+        aspect_ratio: f32,
+        #[serde(default)]
+        fixed_aspect_ratio: bool,
+    },
+    /// A target GameObject is saved as the point it was at.
+    OrbitCamera {
+        #[serde(default)]
+        main: bool,
+        target: [f32; 3],
+        #[serde(default)]
+        yaw: f32,
+        #[serde(default)]
+        pitch: f32,
+        distance: f32,
+        #[serde(default = "damping")]
+        damping: f32,
+        fov: f32,
+        near_plane: f32,
+        far_plane: f32,
+        aspect_ratio: f32,
+        #[serde(default)]
+        fixed_aspect_ratio: bool,
+    },
+    AmbientLight {
+        intensity: f32,
+        color: [f32; 3],
+    },
+    DirectionalLight {
+        intensity: f32,
+        color: [f32; 3],
+    },
+    PointLight {
+        intensity: f32,
+        color: [f32; 3],
+    },
+}
+
+fn visible() -> bool { true }
+
+fn active() -> bool { true }
+
+fn zoom() -> f32 { 1.0 }
+
+fn damping() -> f32 { 0.85 }
+
+/// Refers to a material within an mtl file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaterialDescriptor {
+    /// The path to the mtl file.
+    pub library: String,
+    /// The name of the material within the mtl file.
+    pub name: String,
+}
+
+/// An error encountered while saving or loading a scene.
+#[derive(Debug)]
+pub enum SceneFileError {
+    Io(std::io::Error),
+    Ron(String),
+    Json(serde_json::Error),
+    /// A script could not be found in the registry.
+    Script(String, &'static str),
+    /// A mesh or material could not be loaded.
+    Asset(String, &'static str),
+    /// The GameObject is of a type the level format does not support.
+    Unsupported(String),
+    /// The GameObject has a script that was not registered in the registry so it cannot be saved.
+    UnregisteredScript(String),
+}
+
+impl fmt::Display for SceneFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneFileError::Io(error) => write!(f, "io error: {}", error),
+            SceneFileError::Ron(error) => write!(f, "ron error: {}", error),
+            SceneFileError::Json(error) => write!(f, "json error: {}", error),
+            SceneFileError::Script(name, error) => write!(f, "script {}: {}", name, error),
+            SceneFileError::Asset(path, error) => write!(f, "asset {}: {}", path, error),
+            SceneFileError::Unsupported(name) => write!(f, "{} is of a type that cannot be saved", name),
+            SceneFileError::UnregisteredScript(name) => write!(f, "the script of {} was not registered so it cannot be saved", name),
+        }
+    }
+}
+
+impl std::error::Error for SceneFileError {}
+
+impl From<std::io::Error> for SceneFileError {
+    fn from(error: std::io::Error) -> Self {
+        SceneFileError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SceneFileError {
+    fn from(error: serde_json::Error) -> Self {
+        SceneFileError::Json(error)
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "json")
+}
+
+impl SceneDescriptor {
+    /// Describes a scene.
+    pub fn describe(scene: &Scene) -> Result<Self, SceneFileError> {
+        let children = scene.get_children().iter()
+            .filter(|child| !is_generated(&*child.read().unwrap()))
+            .map(|child| GameObjectDescriptor::describe(&*child.read().unwrap()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // a main camera outside of the hierarchy is saved separately
+        let main_camera = match scene.main_camera.as_ref() {
+            Some(camera) => {
+                let camera = camera.read().unwrap();
+                match scene.find_by_id(&camera.get_id()) {
+                    Some(_) => None,
+                    None => Some(GameObjectDescriptor::describe(camera.as_gameobject())?),
+                }
+            },
+            None => None,
+        };
+
+        Ok(SceneDescriptor {
+            worldspace: scene.worldspace.get_local().into(),
+            children,
+            main_camera,
+        })
+    }
+
+    /// Reads a level file.
+    pub fn read(path: &Path) -> Result<Self, SceneFileError> {
+        let content = fs::read_to_string(path)?;
+        if is_json(path) {
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            ron::from_str(&content).map_err(|error| SceneFileError::Ron(error.to_string()))
+        }
+    }
+
+    /// Writes a level file.
+    pub fn write(&self, path: &Path) -> Result<(), SceneFileError> {
+        let content = if is_json(path) {
+            serde_json::to_string_pretty(self)?
+        } else {
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+                .map_err(|error| SceneFileError::Ron(error.to_string()))?
+        };
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+fn is_generated(game_object: &dyn GameObject) -> bool {
+    game_object.as_any().downcast_ref::<Obj>().is_some_and(|obj| obj.generated)
+}
+
+impl GameObjectDescriptor {
+    /// Describes a GameObject and its children.
+    pub fn describe(game_object: &dyn GameObject) -> Result<Self, SceneFileError> {
+        let any = game_object.as_any();
+        let kind = if let Some(obj) = any.downcast_ref::<Obj>() {
+            obj.describe()
+        } else if let Some(group) = any.downcast_ref::<Group>() {
+            group.describe()
+        } else if let Some(camera) = any.downcast_ref::<FpvCamera>() {
+            camera.describe()
+        } else if let Some(camera) = any.downcast_ref::<OrthographicCamera>() {
+            camera.describe()
+        } else if let Some(camera) = any.downcast_ref::<OrbitCamera>() {
+            camera.describe()
+        } else if let Some(light) = any.downcast_ref::<AmbientLight>() {
+            light.describe()
+        } else if let Some(light) = any.downcast_ref::<DirectionalLight>() {
+            light.describe()
+        } else if let Some(light) = any.downcast_ref::<PointLight>() {
+            light.describe()
+        } else {
+            return Err(SceneFileError::Unsupported(game_object.get_name().to_string()));
+        };
+
+        // a script that cannot be looked up again would be lost on loading
+        let script = game_object.get_script_name();
+        if script.is_none() && game_object.has_script() {
+            return Err(SceneFileError::UnregisteredScript(game_object.get_name().to_string()));
+        }
+
+        let children = game_object.get_children().iter()
+            .filter(|child| !is_generated(&*child.read().unwrap()))
+            .map(|child| GameObjectDescriptor::describe(&*child.read().unwrap()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(GameObjectDescriptor {
+            name: game_object.get_name().to_string(),
+            transform: game_object.get_transform().get_local().into(),
+            active: game_object.get_active(),
+            script,
+            kind,
+            children,
+        })
+    }
+
+    /// Builds the GameObject and its children.
+    /// # Arguments
+    /// * `parent` - The parent GameObject. None places the GameObject directly within the scene of the engine globals.
+    /// * `engine_globals` - The engine globals. The scripts are looked up in its script registry.
+    pub fn build(&self, parent: Option<Arc<RwLock<dyn GameObject>>>, engine_globals: &EngineGlobals) -> Result<Arc<RwLock<dyn GameObject>>, SceneFileError> {
+        let space: Space = self.transform.into();
+        let (position, rotation, scale_factor) = (
+            Some(space.center),
+            Some(space.rotation),
+            Some(Vector3(space.scale_factor.m[0][0], space.scale_factor.m[1][1], space.scale_factor.m[2][2]))
+        );
+        let name = Some(self.name.as_str());
+
+        let game_object: Arc<RwLock<dyn GameObject>> = match &self.kind {
+            GameObjectKind::Obj { visible, mesh, material } => {
+                let script = self.script::<Obj>(engine_globals)?;
+                let obj = match mesh {
+                    Some(mesh) => {
+                        if !Path::new(mesh).is_file() {
+                            return Err(SceneFileError::Asset(mesh.clone(), "The obj file does not exist."));
+                        }
+                        Obj::from_obj(name, mesh, parent, position, rotation, scale_factor, *visible, engine_globals.clone(), script)
+                            .map_err(|_| SceneFileError::Asset(mesh.clone(), "The obj file could not be loaded."))?
+                    },
+                    None => Obj::new_empty(name, parent, position, rotation, scale_factor, *visible, engine_globals.clone(), script),
+                };
+                if let Some(material) = material {
+                    obj.write().unwrap().set_material(&material.library, &material.name, engine_globals.queue.clone())
+                        .map_err(|error| SceneFileError::Asset(material.library.clone(), error))?;
+                }
+                obj
+            },
+            GameObjectKind::Group { visible } => {
+                let script = self.script::<Group>(engine_globals)?;
+                Group::new(name, parent, position, rotation, scale_factor, *visible, engine_globals.clone(), script)
+            },
+            GameObjectKind::FpvCamera { main, offset, fov, near_plane, far_plane, aspect_ratio, fixed_aspect_ratio } => {
+                let script = self.script::<FpvCamera>(engine_globals)?;
+                let camera = FpvCamera::new(
+                    name,
+                    *main,
+                    parent,
+                    position,
+                    rotation,
+                    scale_factor,
+                    offset.map(|[x, y, z]| Vector3(x, y, z)),
+                    *fov,
+                    *near_plane,
+                    *far_plane,
+                    *aspect_ratio,
+                    script,
+                    engine_globals.clone()
+                ).map_err(|error| SceneFileError::Asset(self.name.clone(), error))?;
+                camera.write().unwrap().set_aspect_ratio_fixed(*fixed_aspect_ratio);
+                camera
+            },
+            GameObjectKind::OrthographicCamera { main, height, zoom, near_plane, far_plane, aspect_ratio, fixed_aspect_ratio } => {
+                let script = self.script::<OrthographicCamera>(engine_globals)?;
+                let camera = OrthographicCamera::new(
+                    name,
+                    *main,
+                    parent,
+                    position,
+                    rotation,
+                    scale_factor,
+                    *height,
+                    *near_plane,
+                    *far_plane,
+                    *aspect_ratio,
+                    script,
+                    engine_globals.clone()
+                ).map_err(|error| SceneFileError::Asset(self.name.clone(), error))?;
+                {
+                    let mut write = camera.write().unwrap();
+                    write.set_zoom(*zoom).map_err(|error| SceneFileError::Asset(self.name.clone(), error))?;
+                    write.set_aspect_ratio_fixed(*fixed_aspect_ratio);
+                }
+                camera
+            },
+            GameObjectKind::OrbitCamera { main, target, yaw, pitch, distance, damping, fov, near_plane, far_plane, aspect_ratio, fixed_aspect_ratio } => {
+                let script = self.script::<OrbitCamera>(engine_globals)?;
+                let camera = OrbitCamera::new(
+                    name,
+                    *main,
+                    parent,
+                    OrbitTarget::Point(Vector3(target[0], target[1], target[2])),
+                    *yaw,
+                    *pitch,
+                    *distance,
+                    *fov,
+                    *near_plane,
+                    *far_plane,
+                    *aspect_ratio,
+                    script,
+                    engine_globals.clone()
+                ).map_err(|error| SceneFileError::Asset(self.name.clone(), error))?;
+                {
+                    let mut write = camera.write().unwrap();
+                    write.damping = *damping;
+                    write.set_aspect_ratio_fixed(*fixed_aspect_ratio);
+                }
+                camera
+            },
+            GameObjectKind::AmbientLight { intensity, color } => {
+                let script = self.script::<AmbientLight>(engine_globals)?;
+                AmbientLight::new(name, parent, *intensity, rgb(*color), position, rotation, scale_factor, script, engine_globals.clone())
+            },
+            GameObjectKind::DirectionalLight { intensity, color } => {
+                let script = self.script::<DirectionalLight>(engine_globals)?;
+                DirectionalLight::new(name, parent, *intensity, rgb(*color), position, rotation, scale_factor, script, engine_globals.clone())
+            },
+            GameObjectKind::PointLight { intensity, color } => {
+                let script = self.script::<PointLight>(engine_globals)?;
+                PointLight::new(name, parent, *intensity, rgb(*color), position, rotation, scale_factor, script, engine_globals.clone())
+            },
+        };
+
+        // set the exact local space in case it does not survive being split into its parts
+        game_object.write().unwrap().get_transform_mut().set_local(space);
+        if !self.active {
+            game_object.write().unwrap().set_active(false)
+                .map_err(|error| SceneFileError::Asset(self.name.clone(), error))?;
+        }
+
+        for child in &self.children {
+            let child = child.build(Some(game_object.clone()), engine_globals)?;
+            game_object.write().unwrap().add_child(child);
+        }
+
+        Ok(game_object)
+    }
+
+    fn script<T>(&self, engine_globals: &EngineGlobals) -> Result<Option<Box<Script<T>>>, SceneFileError> where T: Clone + Send + Sync + 'static {
+        match &self.script {
+            Some(name) => engine_globals.script_registry.read().unwrap().get::<T>(name)
+                .map(Some)
+                .map_err(|error| SceneFileError::Script(name.clone(), error)),
+            None => Ok(None),
+        }
+    }
+}
+
+fn rgb([r, g, b]: [f32; 3]) -> RGB {
+    RGB { r, g, b }
+}
+
+impl Scene {
+    /// Saves the scene to a level file. See `scene::serialization`.
+    /// # Arguments
+    /// * `path` - The path of the file. Files ending in `.json` are written as JSON, all others as RON.
+    /// # Examples
+    /// ```no_run
+    /// # let scene = feo_oop_engine::scene::Scene::new(None);
+    /// scene.read().unwrap().save("level.ron").unwrap();
+    /// ```
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SceneFileError> {
+        SceneDescriptor::describe(self)?.write(path.as_ref())
+    }
+
+    /// Loads a scene from a level file. See `scene::serialization`.
+    ///
+    /// The GameObjects of the loaded scene are parented to the new scene. To run it, replace the active scene
+    /// of the SceneManager with it before running the engine or register it and switch to it while running.
+    /// # Arguments
+    /// * `path` - The path of the file. Files ending in `.json` are read as JSON, all others as RON.
+    /// * `engine_globals` - The engine globals. Scripts are looked up in its script registry.
+    /// # Examples
+    /// ```no_run
+    /// # use feo_oop_engine::{FeoEngine, scene::Scene};
+    /// # let mut engine = FeoEngine::init(Scene::new(None), Some(1));
+    /// let scene = Scene::load("level.ron", &engine.globals).unwrap();
+    /// engine.globals.scene_manager.write().unwrap().replace_active(scene);
+    /// ```
+    pub fn load<P: AsRef<Path>>(path: P, engine_globals: &EngineGlobals) -> Result<Arc<RwLock<Scene>>, SceneFileError> {
+        let descriptor = SceneDescriptor::read(path.as_ref())?;
+
+        let scene = Scene::new(Some(descriptor.worldspace.into()));
+        let mut engine_globals = engine_globals.clone();
+        engine_globals.scene = scene.clone();
+
+        for child in &descriptor.children {
+            let child = child.build(None, &engine_globals)?;
+            scene.write().unwrap().add_child(child);
+        }
+
+        // a main camera outside of the hierarchy or else the first main camera within it
+        let main_camera = match descriptor.main_camera.as_ref() {
+            Some(camera) => Some(camera.build(None, &engine_globals)?),
+            None => scene.read().unwrap().query(|game_object| {
+                game_object.as_camera_ref().is_some_and(|camera| camera.is_main())
+            }).next(),
+        };
+        if let Some(camera) = main_camera {
+            let camera = camera.as_camera().unwrap();
+            scene.write().unwrap().main_camera = Some(camera);
+        }
+
+        Ok(scene)
+    }
+}
@@ -0,0 +1,379 @@
+//! A spatial index over the world-space bounds of the GameObjects within a scene.
+//!
+//! The index is a bounding volume hierarchy of axis aligned boxes. The bounds of a GameObject
+//! are the bounds of its triangle meshes moved into world space. GameObjects without meshes such
+//! as groups, cameras and lights are indexed as a point at their world position.
+//!
+//! The index is updated once per frame before the scripts run. Only GameObjects whose world
+//! transform was rebuilt since the last update are looked at again (see `components::transform`)
+//! and each leaf is stored with a margin so small movements do not change the tree at all.
+//! GameObjects that are inactive in the hierarchy are left out of the index.
+//!
+//! Note that GameObjects moved by a script are found at their old position until the next update.
+//! Call `Scene::update_spatial_index` to update it sooner.
+//!
+//! ## Example
+//! ```no_run
+//! # use feo_oop_engine::components::bounds::{Aabb, Ray};
+//! # use feo_math::linear_algebra::vector3::Vector3;
+//! # let scene = feo_oop_engine::scene::Scene::new(None);
+//! let scene = scene.read().unwrap();
+//! let nearby = scene.query_aabb(&Aabb::sphere(Vector3(0.0, 0.0, 0.0), 10.0));
+//! let closest = scene.query_nearest(Vector3(0.0, 0.0, 0.0), |_| true);
+//! let hits = scene.query_ray(&Ray::new(Vector3(0.0, 0.0, 0.0), Vector3(0.0, 0.0, -1.0)).unwrap(), 100.0);
+//! ```
+//!
+use {
+    super::{
+        Scene,
+        game_object::GameObject,
+    },
+    crate::{
+        components::{
+            bounds::{Aabb, Ray},
+            transform::WorldTransform,
+        },
+        registration::{
+            relation::Parent,
+            id::ID,
+        },
+    },
+    feo_math::linear_algebra::vector3::Vector3,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex, RwLock},
+    },
+};
+
+/// How far the stored box of a leaf extends past its bounds.
+const MARGIN: f32 = 0.1;
+
+#[derive(Debug)]
+struct Node {
+    bounds: Aabb, // with the margin for leaves
+    parent: Option<usize>,
+    children: Option<[usize; 2]>,
+    id: Option<ID>, // only leaves
+}
+
+#[derive(Debug)]
+struct Leaf {
+    node: usize,
+    bounds: Aabb,
+    stamp: u64,
+    meshes: usize,
+    updated: u64,
+}
+
+/// A dynamic bounding volume hierarchy.
+#[derive(Debug, Default)]
+struct Bvh {
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    leaves: HashMap<ID, Leaf>,
+    update: u64,
+}
+
+impl Bvh {
+    fn allocate(&mut self, node: Node) -> usize {
+        match self.free.pop() {
+            Some(i) => {
+                self.nodes[i] = node;
+                i
+            },
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Inserts a leaf next to the node whose box grows the least.
+    fn insert_leaf(&mut self, leaf: usize) {
+        let root = match self.root {
+            Some(root) => root,
+            None => {
+                self.root = Some(leaf);
+                self.nodes[leaf].parent = None;
+                return;
+            }
+        };
+
+        let bounds = self.nodes[leaf].bounds;
+        let mut sibling = root;
+        while let Some([a, b]) = self.nodes[sibling].children {
+            let growth = |node: &Node| node.bounds.union(&bounds).surface_area() - node.bounds.surface_area();
+            sibling = if growth(&self.nodes[a]) <= growth(&self.nodes[b]) { a } else { b };
+        }
+
+        let old_parent = self.nodes[sibling].parent;
+        let new_parent = self.allocate(Node {
+            bounds: self.nodes[sibling].bounds.union(&bounds),
+            parent: old_parent,
+            children: Some([sibling, leaf]),
+            id: None,
+        });
+        self.nodes[sibling].parent = Some(new_parent);
+        self.nodes[leaf].parent = Some(new_parent);
+
+        match old_parent {
+            Some(old_parent) => {
+                self.replace_child(old_parent, sibling, new_parent);
+                self.refit(old_parent);
+            },
+            None => self.root = Some(new_parent),
+        }
+    }
+
+    /// Removes a leaf from the tree without freeing it.
+    fn remove_leaf(&mut self, leaf: usize) {
+        let parent = match self.nodes[leaf].parent {
+            Some(parent) => parent,
+            None => {
+                self.root = None;
+                return;
+            }
+        };
+
+        let [a, b] = self.nodes[parent].children.unwrap();
+        let sibling = if a == leaf { b } else { a };
+        let grandparent = self.nodes[parent].parent;
+        self.nodes[sibling].parent = grandparent;
+        match grandparent {
+            Some(grandparent) => {
+                self.replace_child(grandparent, parent, sibling);
+                self.refit(grandparent);
+            },
+            None => self.root = Some(sibling),
+        }
+        self.free.push(parent);
+        self.nodes[leaf].parent = None;
+    }
+
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        if let Some(children) = self.nodes[parent].children.as_mut() {
+            children.iter_mut().filter(|child| **child == old).for_each(|child| *child = new);
+        }
+    }
+
+    /// Rebuilds the boxes of a node and its ancestors.
+    fn refit(&mut self, node: usize) {
+        let mut current = Some(node);
+        while let Some(i) = current {
+            if let Some([a, b]) = self.nodes[i].children {
+                self.nodes[i].bounds = self.nodes[a].bounds.union(&self.nodes[b].bounds);
+            }
+            current = self.nodes[i].parent;
+        }
+    }
+
+    /// Adds a GameObject or moves it if its bounds left the box stored for it.
+    fn set(&mut self, id: ID, bounds: Aabb, stamp: u64, meshes: usize) {
+        let update = self.update;
+        if let Some(leaf) = self.leaves.get_mut(&id) {
+            leaf.bounds = bounds;
+            leaf.stamp = stamp;
+            leaf.meshes = meshes;
+            leaf.updated = update;
+
+            let node = leaf.node;
+            if !self.nodes[node].bounds.contains(&bounds) {
+                self.remove_leaf(node);
+                self.nodes[node].bounds = bounds.expand(MARGIN);
+                self.insert_leaf(node);
+            }
+            return;
+        }
+
+        let node = self.allocate(Node {
+            bounds: bounds.expand(MARGIN),
+            parent: None,
+            children: None,
+            id: Some(id.clone()),
+        });
+        self.insert_leaf(node);
+        self.leaves.insert(id, Leaf { node, bounds, stamp, meshes, updated: update });
+    }
+
+    fn remove(&mut self, id: &ID) {
+        if let Some(leaf) = self.leaves.remove(id) {
+            self.remove_leaf(leaf.node);
+            self.free.push(leaf.node);
+        }
+    }
+
+    /// Calls `visit` with every leaf whose box passes `test` without visiting the branches that fail it.
+    fn traverse<T, V>(&self, mut test: T, mut visit: V) where T: FnMut(&Aabb) -> bool, V: FnMut(&ID, &Aabb) {
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            if !test(&node.bounds) {
+                continue;
+            }
+            match (node.children, node.id.as_ref()) {
+                (Some([a, b]), _) => {
+                    stack.push(a);
+                    stack.push(b);
+                },
+                (None, Some(id)) => {
+                    let bounds = self.leaves[id].bounds;
+                    if test(&bounds) {
+                        visit(id, &bounds);
+                    }
+                },
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn nearest<F>(&self, point: Vector3<f32>, mut filter: F) -> Option<(ID, f32)> where F: FnMut(&ID) -> bool {
+        let mut best: Option<(ID, f32)> = None;
+        let mut stack: Vec<(usize, f32)> = self.root.map(|root| (root, self.nodes[root].bounds.distance_squared(point))).into_iter().collect();
+        while let Some((i, distance)) = stack.pop() {
+            if best.as_ref().is_some_and(|(_, best)| distance > *best) {
+                continue;
+            }
+            let node = &self.nodes[i];
+            match (node.children, node.id.as_ref()) {
+                (Some([a, b]), _) => {
+                    // visit the closer child first
+                    let (a, b) = ((a, self.nodes[a].bounds.distance_squared(point)), (b, self.nodes[b].bounds.distance_squared(point)));
+                    let (near, far) = if a.1 <= b.1 { (a, b) } else { (b, a) };
+                    stack.push(far);
+                    stack.push(near);
+                },
+                (None, Some(id)) => {
+                    let distance = self.leaves[id].bounds.distance_squared(point);
+                    if best.as_ref().is_none_or(|(_, best)| distance < *best) && filter(id) {
+                        best = Some((id.clone(), distance));
+                    }
+                },
+                (None, None) => {}
+            }
+        }
+        best.map(|(id, distance)| (id, distance.sqrt()))
+    }
+}
+
+/// \[backend\] The bounding volume hierarchy of a scene.
+#[derive(Default)]
+pub(crate) struct SpatialIndex {
+    tree: Mutex<Bvh>,
+}
+
+impl SpatialIndex {
+    /// Updates the leaves of the GameObjects that moved, appeared or disappeared since the last update.
+    pub(crate) fn update(&self, scene: &Scene) {
+        let mut tree = self.tree.lock().unwrap();
+        tree.update += 1;
+
+        let world = scene.get_world_transform();
+        scene.get_children().iter().for_each(|child| update_tree(&mut tree, child, &world));
+
+        let update = tree.update;
+        let removed: Vec<ID> = tree.leaves.iter()
+            .filter(|(_, leaf)| leaf.updated != update)
+            .map(|(id, _)| id.clone())
+            .collect();
+        removed.iter().for_each(|id| tree.remove(id));
+    }
+}
+
+/// Walks a GameObject and its children passing the world transform down so no parent has to be locked twice.
+fn update_tree(tree: &mut Bvh, game_object: &Arc<RwLock<dyn GameObject>>, parent: &WorldTransform) {
+    let game_object = game_object.read().unwrap();
+    if !game_object.get_active() {
+        return;
+    }
+
+    let id = game_object.get_id();
+    let world = game_object.get_transform().resolve(parent);
+    let meshes = game_object.get_triangle_mesh();
+
+    let unchanged = tree.leaves.get(&id).is_some_and(|leaf| leaf.stamp == world.stamp && leaf.meshes == meshes.len());
+    if unchanged {
+        let update = tree.update;
+        tree.leaves.get_mut(&id).unwrap().updated = update;
+    } else {
+        let local = meshes.iter()
+            .filter_map(|mesh| mesh.get_bounds())
+            .reduce(|a, b| a.union(&b));
+        let bounds = match local {
+            Some(local) => local.transform(&world.local_to_world),
+            None => Aabb::point(world.local_to_world.center),
+        };
+        tree.set(id, bounds, world.stamp, meshes.len());
+    }
+
+    game_object.get_children().iter().for_each(|child| update_tree(tree, child, &world));
+}
+
+impl Clone for SpatialIndex {
+    fn clone(&self) -> Self {
+        SpatialIndex::default() // rebuilt on the next update
+    }
+}
+
+impl std::fmt::Debug for SpatialIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpatialIndex")
+            .field("len", &self.tree.lock().unwrap().leaves.len()).finish()
+    }
+}
+
+impl Scene {
+    /// \[backend\] Updates the spatial index. Run once per frame before the scripts.
+    ///
+    /// Call it after moving GameObjects for the queries to see their new positions within the same frame.
+    /// Do not call it while holding a write lock on a GameObject within the scene.
+    pub fn update_spatial_index(&self) {
+        self.spatial.update(self);
+    }
+
+    /// Gets the world-space bounds a GameObject was indexed with.
+    /// # Arguments
+    /// * `id` - The ID of the GameObject.
+    pub fn get_bounds(&self, id: &ID) -> Option<Aabb> {
+        self.spatial.tree.lock().unwrap().leaves.get(id).map(|leaf| leaf.bounds)
+    }
+
+    /// Finds every GameObject whose bounds overlap a box.
+    /// # Arguments
+    /// * `bounds` - The box in world space.
+    pub fn query_aabb(&self, bounds: &Aabb) -> Vec<Arc<RwLock<dyn GameObject>>> {
+        let mut ids = Vec::new();
+        self.spatial.tree.lock().unwrap().traverse(
+            |node| node.intersects(bounds),
+            |id, _| ids.push(id.clone())
+        );
+        ids.iter().filter_map(|id| self.find_by_id(id)).collect()
+    }
+
+    /// Finds the GameObject whose bounds are closest to a point and the distance to them.
+    /// # Arguments
+    /// * `point` - The point in world space.
+    /// * `filter` - Returns false for GameObjects to skip. e.g. `|id| *id != my_id`
+    pub fn query_nearest<F>(&self, point: Vector3<f32>, filter: F) -> Option<(Arc<RwLock<dyn GameObject>>, f32)>
+    where F: FnMut(&ID) -> bool {
+        let (id, distance) = self.spatial.tree.lock().unwrap().nearest(point, filter)?;
+        self.find_by_id(&id).map(|game_object| (game_object, distance))
+    }
+
+    /// Finds every GameObject whose bounds a ray passes through along with the distance at which it enters them.
+    /// Sorted from closest to furthest.
+    /// # Arguments
+    /// * `ray` - The ray in world space.
+    /// * `max_distance` - The distance along the ray after which GameObjects are ignored.
+    pub fn query_ray(&self, ray: &Ray, max_distance: f32) -> Vec<(Arc<RwLock<dyn GameObject>>, f32)> {
+        let mut hits = Vec::new();
+        self.spatial.tree.lock().unwrap().traverse(
+            |node| node.intersect_ray(ray, max_distance).is_some(),
+            |id, bounds| hits.push((id.clone(), bounds.intersect_ray(ray, max_distance).unwrap()))
+        );
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+        hits.into_iter()
+            .filter_map(|(id, distance)| self.find_by_id(&id).map(|game_object| (game_object, distance)))
+            .collect()
+    }
+}
@@ -3,11 +3,13 @@ use {
     super::{
         swap::Swap,
         globals::EngineGlobals,
+        new_executor_and_spawner,
     },
     crate::{
         scene::{
             Scene,
-            game_object::GameObject,
+            game_object::{GameObject, GameObjectHandle},
+            query::Descendants,
         },
         registration::{
             relation::{
@@ -15,9 +17,11 @@ use {
                 ParentWrapper
             },
             id::ID,
-        }
+        },
+        event::UserEvent,
     },
     std::{
+        any::Any,
         future::Future,
         sync::mpsc::{
             Receiver,
@@ -35,6 +39,7 @@ use {
         }
     },
     futures::{
+        executor::block_on,
         future::{
             BoxFuture, 
             FutureExt
@@ -45,11 +50,14 @@ use {
         },
     },
     rayon::slice::ParallelSliceMut,
+    winit::event::Event,
 };
 
 pub struct Executor {
     pub ready: Vec<Arc<RwLock<Box<dyn GameObject>>>>,
     pub queue: Receiver<Arc<Task>>,
+    pub engine_globals: EngineGlobals,
+    pub(crate) despawning: Vec<ID>, // the roots of the subtrees currently being despawned
 }
 
 #[derive(Clone)]
@@ -112,38 +120,49 @@ impl Executor {
                 match swap {
                     // By swapping out the physical pointers rather than the interior it makes it possible to store a pointer to swap back in later
                     Swap::SwapParent(id, replacement) => { //
-                        let mut found = false;
-                        let mut queue = scene.read().unwrap().get_children().clone();
-                        
-                        while let (Some(old), false) = (queue.pop(), found) {
+                        let old = scene.read().unwrap().find_by_id(&id);
+                        let mut found = old.is_some();
+
+                        if let Some(old) = old {
+                            self.despawn(old.clone(), false, scene.clone());
+
                             let read = old.read().unwrap(); // will not actually be changing the target object
-                            queue.append(&mut read.get_children());
-                            if read.get_id() == id {
-                                let children = read.get_children();
-                                let parent = read.get_parent();
-    
-                                // direct children to the replacement
-                                children.into_iter().for_each(|child| {
-                                    // set child object's parent object to be the new object
-                                    unsafe { child.write().unwrap().set_parent(ParentWrapper::GameObject(replacement.clone()))};
-                                    
-                                    // set replacement's child objects to be the new object
-                                    replacement.write().unwrap().add_child(child);
-                                });
-    
-                                // direct parent to the replacement
-                                match parent.clone() { // shadowing
-                                    ParentWrapper::GameObject(parent) => unsafe { 
-                                        parent.write().unwrap().replace_child(old.clone(), replacement.clone()).unwrap();
-                                    },
-                                    ParentWrapper::Scene(p) => unsafe {
-                                        p.write().unwrap().replace_child(old.clone(), replacement.clone()).unwrap();
-                                    }
+                            let children = read.get_children();
+                            let parent = read.get_parent().expect("the parent no longer exists");
+
+                            // direct children to the replacement
+                            let replacement_id = replacement.read().unwrap().get_id();
+                            children.into_iter().for_each(|child| {
+                                // set child object's parent object to be the new object
+                                unsafe { child.write().unwrap().set_parent(ParentWrapper::GameObject(replacement.clone()))};
+                                self.send(UserEvent::ParentChanged(child.read().unwrap().get_id(), Some(replacement_id.clone())));
+                                
+                                // set replacement's child objects to be the new object
+                                replacement.write().unwrap().add_child(child);
+                            });
+
+                            // direct parent to the replacement
+                            match parent.clone() { // shadowing
+                                ParentWrapper::GameObject(parent) => unsafe { 
+                                    parent.write().unwrap().replace_child(old.clone(), replacement.clone()).unwrap();
+                                },
+                                ParentWrapper::Scene(p) => unsafe {
+                                    p.write().unwrap().replace_child(old.clone(), replacement.clone()).unwrap();
                                 }
-                                unsafe { replacement.write().unwrap().set_parent(parent); }
-    
-                                found = true;
                             }
+                            let parent_id = parent.get_id();
+                            unsafe { replacement.write().unwrap().set_parent(parent); }
+                            drop(read);
+
+                            // update the index
+                            let scene_read = scene.read().unwrap();
+                            scene_read.index.remove_tree(&old);
+                            scene_read.index.insert_tree(&replacement);
+                            drop(scene_read);
+
+                            self.send(UserEvent::ChildRemoved(parent_id.clone(), id.clone()));
+                            self.send(UserEvent::ChildAdded(parent_id, replacement_id.clone()));
+                            self.send(UserEvent::Spawned(replacement_id));
                         }
                         
                         // Check Camera
@@ -155,30 +174,9 @@ impl Executor {
                                 // Note that swapping a main camera parent does not make sense unless the main camera is part of the game_object tree since those objects would not be drawn.
                                 // If your intent is to toggle this group the objects and toggle their visibility attribute instead.
                                 assert!(found);
-                                // // direct children to the replacement
-                                // let children = old_camera_read.get_children();
-                                // let parent = old_camera_read.get_parent();
-                                // let as_gameobject = old_camera_read.cast_gameobject_arc_rwlock(old_camera.clone());
-                                // children.into_iter().for_each(|child| {
-                                //     // set child object's parent object to be the new object
-                                //     unsafe { child.write().unwrap().set_parent(ParentWrapper::GameObject(replacement.clone())) };
-                                //     // set replacement's child objects to be the new object
-                                //     replacement.write().unwrap().add_child(child);
-                                // });
-    
-                                // // direct parent to the replacement
-                                // match parent.clone() {
-                                //     ParentWrapper::GameObject(p) => unsafe { 
-                                //         p.write().unwrap().replace_child(as_gameobject.clone(), replacement.clone()).unwrap();
-                                //     },
-                                //     ParentWrapper::Scene(_) => unsafe { 
-                                //         scene_rw.replace_child(as_gameobject, replacement.clone()).unwrap();
-                                //     },
-                                // }
-                                // unsafe { replacement.write().unwrap().set_parent(parent); }
     
                                 // replace the old camera with the replacement in the main_camera slot
-                                scene_rw.main_camera = Some(replacement.clone().read().unwrap().cast_camera_arc_rwlock(replacement.clone()).unwrap()); 
+                                scene_rw.main_camera = Some(replacement.as_camera().unwrap()); 
     
                                 found = true;
                             }
@@ -190,28 +188,36 @@ impl Executor {
                     },
                     // cutting is fine here
                     Swap::SwapFull(id, replacement) => {
-                        let mut found = false;
-                        let mut queue = scene.read().unwrap().get_children().clone();
-                        
-                        while let (Some(old), false) = (queue.pop(), found) {
-                            let read = old.read().unwrap(); // will not actually be changing the target object
-                            queue.append(&mut read.get_children());
-                            if read.get_id() == id {
-                                let parent = read.get_parent();
-    
-                                // direct parent to the replacement
-                                match parent.clone() { // shadowing
-                                    ParentWrapper::GameObject(parent) => unsafe { 
-                                        parent.write().unwrap().replace_child(old.clone(), replacement.clone()).unwrap();
-                                    },
-                                    ParentWrapper::Scene(p) => unsafe {
-                                        p.write().unwrap().replace_child(old.clone(), replacement.clone()).unwrap();
-                                    }
+                        let old = scene.read().unwrap().find_by_id(&id);
+                        let mut found = old.is_some();
+
+                        if let Some(old) = old {
+                            self.despawn(old.clone(), true, scene.clone());
+
+                            let parent = old.read().unwrap().get_parent().expect("the parent no longer exists");
+
+                            // direct parent to the replacement
+                            match parent.clone() { // shadowing
+                                ParentWrapper::GameObject(parent) => unsafe { 
+                                    parent.write().unwrap().replace_child(old.clone(), replacement.clone()).unwrap();
+                                },
+                                ParentWrapper::Scene(p) => unsafe {
+                                    p.write().unwrap().replace_child(old.clone(), replacement.clone()).unwrap();
                                 }
-                                unsafe { replacement.write().unwrap().set_parent(parent); }
-    
-                                found = true;
                             }
+                            let parent_id = parent.get_id();
+                            unsafe { replacement.write().unwrap().set_parent(parent); }
+
+                            // update the index
+                            let scene_read = scene.read().unwrap();
+                            scene_read.index.remove_tree(&old);
+                            scene_read.index.insert_tree(&replacement);
+                            drop(scene_read);
+
+                            let replacement_id = replacement.read().unwrap().get_id();
+                            self.send(UserEvent::ChildRemoved(parent_id.clone(), id.clone()));
+                            self.send(UserEvent::ChildAdded(parent_id, replacement_id.clone()));
+                            self.send(UserEvent::Spawned(replacement_id));
                         }
                         
                         // Check Camera
@@ -219,23 +225,13 @@ impl Executor {
                         if let Some(old_camera) = scene_rw.main_camera.clone() {
                             let old_camera_read = old_camera.read().unwrap();
                             if id == old_camera_read.get_id() {
-                                let parent = old_camera_read.get_parent();
-                                // Not needed because It is done in above loop based on children of parents
-                                // let as_gameobject = old_camera_read.cast_gameobject_arc_rwlock(old_camera.clone());
-                                // match parent.clone() {
-                                //     ParentWrapper::GameObject(p) => unsafe { 
-                                //         p.write().unwrap().replace_child(as_gameobject.clone(), replacement.clone()).unwrap();
-                                //     },
-                                //     ParentWrapper::Scene(_) => unsafe { 
-                                //         scene_rw.replace_child(as_gameobject.clone(), replacement.clone()).unwrap();
-                                //     },
-                                // }
+                                let parent = old_camera_read.get_parent().expect("the parent no longer exists");
 
                                 // needed in the case that someone does not wish to have scripts on a main camera with scene parent and thus does not 
                                 // tell parent that camera is its child.
                                 unsafe { replacement.write().unwrap().set_parent(parent); }
 
-                                scene_rw.main_camera = Some(replacement.clone().read().unwrap().cast_camera_arc_rwlock(replacement.clone()).unwrap()); 
+                                scene_rw.main_camera = Some(replacement.as_camera().unwrap()); 
 
                                 found = true;
                             }
@@ -247,26 +243,39 @@ impl Executor {
                     },
                    
                     Swap::Delete(id) => {
-                        let mut found = false;
-                        let mut queue = scene.read().unwrap().get_children().clone();
-                        while let Some(old) = queue.pop() {
-                            let read = old.read().unwrap(); // will not actually be changing the target object
-                            queue.append(&mut read.get_children());
-                            if read.get_id() == id {
-                                let parent = read.get_parent();
-    
-                                // direct parent to the replacement
-                                match parent.clone() { // shadowing
-                                    ParentWrapper::GameObject(p) => unsafe { 
-                                        p.write().unwrap().remove_child(old.clone()).unwrap();
-                                    },
-                                    ParentWrapper::Scene(p) => unsafe {
-                                        p.write().unwrap().remove_child(old.clone()).unwrap();
-                                    }
+                        // already deleted or being deleted
+                        if !id.is_alive() || self.despawning.contains(&id) { return; }
+
+                        // the scene must not be locked while the handlers run as they may swap within it
+                        let old = scene.read().unwrap().find_by_id(&id);
+                        if let Some(old) = old {
+                            self.despawn(old, true, scene.clone());
+
+                            // deleted while despawning
+                            if !id.is_alive() { return; }
+                        }
+
+                        let old = scene.read().unwrap().find_by_id(&id);
+                        let found = old.is_some();
+
+                        if let Some(old) = old.clone() {
+                            let parent = old.read().unwrap().get_parent().expect("the parent no longer exists");
+                            let parent_id = parent.get_id();
+
+                            // direct parent to the replacement
+                            match parent { // shadowing
+                                ParentWrapper::GameObject(p) => unsafe { 
+                                    p.write().unwrap().remove_child(old.clone()).unwrap();
+                                },
+                                ParentWrapper::Scene(p) => unsafe {
+                                    p.write().unwrap().remove_child(old.clone()).unwrap();
                                 }
-    
-                                found = true;
                             }
+
+                            // update the index
+                            scene.read().unwrap().index.remove_tree(&old);
+
+                            self.send(UserEvent::ChildRemoved(parent_id, id.clone()));
                         }
                         
                         // Check Camera
@@ -278,7 +287,36 @@ impl Executor {
                         
                         if !found {
                             panic!("could not find the ID.");
-                        } 
+                        }
+
+                        // release the IDs of the removed subtree
+                        Descendants::new(old.into_iter().collect()).for_each(|game_object| {
+                            let id = game_object.read().unwrap().get_id();
+                            id.get_system().free(&id).unwrap();
+                        });
+                    },
+                    Swap::Detach(id) => self.detach(id, scene.clone()),
+                    Swap::Release(id, on_detach) => {
+                        self.detach(id, scene.clone());
+                        on_detach();
+                    },
+                    Swap::Spawn(game_object) => {
+                        let parent = game_object.read().unwrap().get_parent().expect("the parent no longer exists");
+                        let parent_id = parent.get_id();
+
+                        match parent {
+                            ParentWrapper::GameObject(p) => {
+                                p.write().unwrap().add_child(game_object.clone());
+                                scene.read().unwrap().index.insert_tree(&game_object);
+                            },
+                            ParentWrapper::Scene(p) => {
+                                p.write().unwrap().add_child(game_object.clone()); // indexes the tree
+                            }
+                        }
+
+                        let id = game_object.read().unwrap().get_id();
+                        self.send(UserEvent::ChildAdded(parent_id, id.clone()));
+                        self.send(UserEvent::Spawned(id));
                     },
                     _ => { panic!("not possible"); }
                 }
@@ -286,6 +324,59 @@ impl Executor {
         }
     }
 
+    /// Removes a GameObject from its parent without deleting it.
+    fn detach(&self, id: ID, scene: Arc<RwLock<Scene>>) {
+        // already deleted or detached
+        let old = match scene.read().unwrap().find_by_id(&id) {
+            Some(old) => old,
+            None => return,
+        };
+
+        let parent = old.read().unwrap().get_parent().expect("the parent no longer exists");
+        let parent_id = parent.get_id();
+
+        match parent {
+            ParentWrapper::GameObject(p) => unsafe { 
+                p.write().unwrap().remove_child(old.clone()).unwrap();
+            },
+            ParentWrapper::Scene(p) => unsafe {
+                p.write().unwrap().remove_child(old.clone()).unwrap();
+            }
+        }
+
+        // update the index
+        scene.read().unwrap().index.remove_tree(&old);
+
+        self.send(UserEvent::ChildRemoved(parent_id, id));
+    }
+
+    /// Delivers a `Despawning` event to a GameObject that is about to be removed and runs the handlers to completion.
+    fn despawn(&self, game_object: Arc<RwLock<dyn GameObject>>, with_children: bool, scene: Arc<RwLock<Scene>>) {
+        let id = game_object.read().unwrap().get_id();
+        let event = Event::UserEvent(UserEvent::Despawning(id.clone()));
+
+        let (mut executor, spawner) = new_executor_and_spawner(self.engine_globals.clone());
+        executor.despawning = self.despawning.clone();
+        executor.despawning.push(id);
+
+        if with_children {
+            game_object.write().unwrap().spawn_script_handler(game_object.clone(), spawner, event);
+        } else {
+            game_object.write().unwrap().spawn_own_script_handler(game_object.clone(), spawner, event);
+        }
+        executor.run(scene);
+    }
+
+    /// \[backend\] Delivers a `Despawning` event to every GameObject within a scene that is being switched away from.
+    pub(crate) fn unload(&self, scene: Arc<RwLock<Scene>>) {
+        scene.read().unwrap().get_children().into_iter().for_each(|child| self.despawn(child, true, scene.clone()));
+    }
+
+    /// Broadcasts an event to every GameObject.
+    fn send(&self, event: UserEvent<Arc<dyn Any + Send + Sync>>) {
+        block_on(self.engine_globals.event_loop_proxy.lock()).send_event(event).unwrap();
+    }
+
     #[inline(always)]
     fn order_id(game_object: Arc<RwLock<dyn GameObject>>, n: &mut usize) -> Vec<(ID, usize)>{
         let read_lock = game_object.read().unwrap();
@@ -311,6 +402,96 @@ impl Executor {
 
         let hash_map: HashMap<ID, usize> = order.into_iter().collect();
 
-        swaps.par_sort_unstable_by_key(|swap| hash_map.get(swap.get_id().unwrap()));
+        // spawns go first so that a GameObject spawned and released on the same frame is detached after it is added
+        swaps.par_sort_unstable_by_key(|swap| (
+            swap.get_id().ok().and_then(|id| hash_map.get(id)),
+            !matches!(swap, Swap::Spawn(_))
+        ));
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            scene::game_object::group::Group,
+            scene::manager::SceneManager,
+            scripting::{Script, registry::ScriptRegistry},
+            registration::id::IDSystem,
+        },
+        std::{sync::mpsc, thread, time::Duration},
+        vulkano::{
+            device::{Device, DeviceExtensions},
+            instance::{Instance, PhysicalDevice},
+        },
+        vulkano_win::VkSurfaceBuild,
+        winit::{
+            event_loop::EventLoop,
+            platform::unix::EventLoopExtUnix,
+            window::WindowBuilder,
+        },
+    };
+
+    fn nothing(_: Arc<RwLock<Group>>, _: EngineGlobals) -> BoxFuture<'static, Swap> {
+        Box::pin(async { Swap::None })
+    }
+
+    // deletes its sibling while it is being deleted itself
+    fn delete_sibling(this: Arc<RwLock<Group>>, engine_globals: EngineGlobals, event: Event<'static, UserEvent<Arc<dyn Any + Send + Sync>>>) -> BoxFuture<'static, Swap> {
+        Box::pin(async move {
+            match event {
+                Event::UserEvent(UserEvent::Despawning(id)) if id == this.read().unwrap().get_id() => {
+                    let sibling = engine_globals.scene.read().unwrap().find_path("sibling").unwrap();
+                    let id = sibling.read().unwrap().get_id();
+                    Swap::Delete(id)
+                },
+                _ => Swap::None
+            }
+        })
+    }
+
+    #[test]
+    #[ignore = "needs a vulkan device and a window"]
+    fn delete_from_despawning_handler() {
+        let scene = Scene::new(None);
+        let event_loop = EventLoop::<UserEvent<Arc<dyn Any + Send + Sync>>>::new_any_thread();
+
+        let instance = Instance::new(None, &vulkano_win::required_extensions(), None).unwrap();
+        let physical = PhysicalDevice::enumerate(&instance).next().unwrap();
+        let surface = WindowBuilder::new().with_visible(false).build_vk_surface(&event_loop, instance.clone()).unwrap();
+        let queue_family = physical.queue_families().find(|family| family.supports_graphics()).unwrap();
+        let (_, mut queues) = Device::new(physical, physical.supported_features(), &DeviceExtensions::none(), [(queue_family, 0.5)].iter().cloned()).unwrap();
+
+        let engine_globals = EngineGlobals {
+            queue: queues.next().unwrap(),
+            surface,
+            scene: scene.clone(),
+            event_loop_proxy: Arc::new(futures::lock::Mutex::new(event_loop.create_proxy())),
+            id_system: IDSystem::default(),
+            script_registry: Arc::new(RwLock::new(ScriptRegistry::default())),
+            scene_manager: Arc::new(RwLock::new(SceneManager::new(SceneManager::DEFAULT_SCENE, scene.clone()))),
+        };
+
+        let script = Script::new_boxed(Box::pin(nothing), Box::pin(nothing), Some(Box::pin(delete_sibling)));
+        let dying = Group::new(Some("dying"), None, None, None, None, true, engine_globals.clone(), Some(script));
+        let sibling = Group::new(Some("sibling"), None, None, None, None, true, engine_globals.clone(), None);
+        let dying_id = dying.read().unwrap().get_id();
+        scene.write().unwrap().add_child(dying);
+        scene.write().unwrap().add_child(sibling);
+
+        // the executor used to hang with the scene locked while the handler deleted the sibling
+        let (done, finished) = mpsc::channel();
+        let run_scene = scene.clone();
+        thread::spawn(move || {
+            let (executor, spawner) = new_executor_and_spawner(engine_globals);
+            spawner.spawn(async move { Swap::Delete(dying_id) });
+            drop(spawner);
+            executor.run(run_scene);
+            done.send(()).unwrap();
+        });
+        finished.recv_timeout(Duration::from_secs(10)).expect("deleting from a Despawning handler deadlocked");
+
+        assert!(scene.read().unwrap().get_children().is_empty());
     }
 }
@@ -8,6 +8,10 @@ use vulkano::device::Queue;
 
 use crate::registration::id::IDSystem;
 
+use super::registry::ScriptRegistry;
+
+use crate::scene::manager::SceneManager;
+
 use {
     crate::{
         scene::Scene,
@@ -32,6 +36,8 @@ pub struct EngineGlobals {
     pub scene: Arc<RwLock<Scene>>,
     pub event_loop_proxy: Arc<futures::lock::Mutex<EventLoopProxy<UserEvent<Arc<dyn Any + 'static + Send + Sync>>>>>,
     pub id_system: IDSystem,
+    pub script_registry: Arc<RwLock<ScriptRegistry>>,
+    pub scene_manager: Arc<RwLock<SceneManager>>,
 }
 
 pub trait Global: GlobalClone + Debug + Any + Send + Sync + 'static {
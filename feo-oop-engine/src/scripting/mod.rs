@@ -39,22 +39,33 @@ use {
 pub mod globals;
 pub mod executor;
 pub mod swap;
+pub mod registry;
 
 
 /// A trait that provides scriptable functionality.
 pub trait Scriptable {
     fn spawn_script_core(&mut self, this: Arc<RwLock<dyn GameObject>>, spawner: Spawner); // TODO: return result 
     fn spawn_script_handler(&mut self, this: Arc<RwLock<dyn GameObject>>, spawner: Spawner, event: Event<'static, UserEvent<Arc<dyn Any + Send + Sync>>>);
+    /// Spawns the event handler of this GameObject but not of its children.
+    fn spawn_own_script_handler(&mut self, this: Arc<RwLock<dyn GameObject>>, spawner: Spawner, event: Event<'static, UserEvent<Arc<dyn Any + Send + Sync>>>);
     fn get_globals(&self) -> Result<Box<dyn Global>, &'static str>;
     fn set_globals(&mut self, globals: Box<dyn Global>) -> Result<(), &'static str>;
+    /// Checks if the GameObject has a script.
+    fn has_script(&self) -> bool;
+    /// Resets the scripts of this GameObject and its children so that their start functions run again.
+    fn restart_script(&mut self);
+    /// Gets the name the script was registered under. See `registry::ScriptRegistry`.
+    fn get_script_name(&self) -> Option<String>;
 }
 
 pub type BoxedStartFn<T> = Pin<Box<fn(Arc<RwLock<T>>, EngineGlobals) -> BoxFuture<'static, Swap>>>;
 pub type BoxedFrameFn<T> = Pin<Box<fn(Arc<RwLock<T>>, EngineGlobals) -> BoxFuture<'static, Swap>>>;
 pub type BoxedEventHandlerFn<T> = Pin<Box<fn(Arc<RwLock<T>>, EngineGlobals, Event<'static, UserEvent<Arc<dyn Any + Send + Sync>>>) -> BoxFuture<'static, Swap>>>;
+pub type BoxedExpireFn = Pin<Box<fn(Arc<RwLock<dyn GameObject>>, EngineGlobals) -> BoxFuture<'static, Swap>>>;
 
 /// A struct that provides a container for a scripts datatypes.
 pub struct Script<T> where T: ?Sized + Send + 'static{
+    pub name: Option<String>, // set when registered
     pub has_started: bool,
     pub globals: Option<Box<dyn Global>>,
     pub start: BoxedStartFn<T>,
@@ -65,6 +76,7 @@ pub struct Script<T> where T: ?Sized + Send + 'static{
 impl<T: ?Sized + Send + 'static> std::fmt::Debug for Script<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Script")
+            .field("name", &self.name)
             .field("has_started", &self.has_started)
             .field("globals", &self.globals)
             .field("start", &self.start)
@@ -79,6 +91,7 @@ impl<T> Script<T> where T: ?Sized + Send + 'static{
             frame: BoxedFrameFn<T>,
             event_handler: Option<BoxedEventHandlerFn<T>>) -> Box<Script<T>> {
         Box::new(Script{
+            name: None,
             has_started: false,
             globals: None,
             start,
@@ -88,11 +101,13 @@ impl<T> Script<T> where T: ?Sized + Send + 'static{
     }
 }
 
+/// Copies a script with fresh state. The copy runs its start function again which builds its own globals.
 impl<T> Clone for Script<T> where T: Clone + Send + 'static{
     fn clone(&self) -> Self {
         Script{
-            has_started: self.has_started,
-            globals: self.globals.clone(),
+            name: self.name.clone(),
+            has_started: false,
+            globals: None,
             start: self.start.clone(), //Box::pin(*self.start),
             frame: self.frame.clone(), //Box::pin(*self.frame),
             event_handler: self.event_handler.clone()
@@ -108,5 +123,5 @@ impl<T> Clone for Script<T> where T: Clone + Send + 'static{
 pub fn new_executor_and_spawner(engine_globals: EngineGlobals) -> (Executor, Spawner) {
     const MAX_QUEUED_TASKS: usize = 10_000;
     let (task_sender, queue) = sync_channel(MAX_QUEUED_TASKS);
-    (Executor {queue, ready: Vec::new() }, Spawner { task_sender, engine_globals })
+    (Executor {queue, ready: Vec::new(), engine_globals: engine_globals.clone(), despawning: Vec::new() }, Spawner { task_sender, engine_globals })
 }
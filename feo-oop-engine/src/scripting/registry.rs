@@ -0,0 +1,60 @@
+//! A registry of scripts that can be referred to by name.
+//!
+//! Scenes that are loaded from a file refer to their scripts by name. Register
+//! every script a scene file uses before loading it.
+//!
+use {
+    super::Script,
+    std::{
+        any::Any,
+        collections::HashMap,
+    },
+};
+
+/// Maps names to scripts.
+#[derive(Default)]
+pub struct ScriptRegistry {
+    scripts: HashMap<String, Box<dyn Any + Send + Sync>>,
+}
+
+impl ScriptRegistry {
+    /// Registers a script under a name replacing any script with the same name.
+    /// # Arguments
+    /// * `name` - The name that scene files use to refer to the script.
+    /// * `script` - The script.
+    /// # Examples
+    /// ```no_run
+    /// # use feo_oop_engine::scene::game_object::obj::Obj;
+    /// # fn f(engine_globals: feo_oop_engine::scripting::globals::EngineGlobals, script: Box<feo_oop_engine::scripting::Script<Obj>>) {
+    /// engine_globals.script_registry.write().unwrap().register("spin", script);
+    /// # }
+    /// ```
+    pub fn register<T>(&mut self, name: &str, mut script: Box<Script<T>>) where T: Clone + Send + Sync + 'static {
+        script.name = Some(name.to_string());
+        self.scripts.insert(name.to_string(), script);
+    }
+
+    /// Gets a copy of the script registered under a name.
+    /// # Arguments
+    /// * `name` - The name the script was registered under.
+    pub fn get<T>(&self, name: &str) -> Result<Box<Script<T>>, &'static str> where T: Clone + Send + Sync + 'static {
+        match self.scripts.get(name) {
+            Some(script) => match script.downcast_ref::<Script<T>>() {
+                Some(script) => Ok(Box::new(script.clone())),
+                None => Err("The script was registered for another type of GameObject."),
+            },
+            None => Err("No script is registered under that name."),
+        }
+    }
+
+    /// Checks if a script is registered under a name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.scripts.contains_key(name)
+    }
+}
+
+impl std::fmt::Debug for ScriptRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.scripts.keys()).finish()
+    }
+}
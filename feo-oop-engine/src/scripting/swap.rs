@@ -20,6 +20,9 @@ pub enum Swap{
         Arc<RwLock<dyn GameObject>>  // with this 
     ), // replaces the object and all its child objects
     Delete(ID), // Deletes the object with ID 
+    Spawn(Arc<RwLock<dyn GameObject>>), // Adds the object to its parent
+    Detach(ID), // Removes the object with ID from its parent without deleting it
+    Release(ID, Box<dyn FnOnce() + Send + Sync>), // Detaches the object with ID and then runs the function. see game_object::pool
     None // don't swap
 }
 
@@ -29,6 +32,9 @@ impl Swap{
             Swap::SwapParent(id, _) => Ok(id),
             Swap::SwapFull(id, _) => Ok(id),
             Swap::Delete(id) => Ok(id),
+            Swap::Detach(id) => Ok(id),
+            Swap::Release(id, _) => Ok(id),
+            Swap::Spawn(_) => Err("Spawn type has no ID"),
             Swap::None => Err("None type has no ID")
         }
     }